@@ -0,0 +1,493 @@
+//! Critère de la récompense moyenne, pour les tâches continues (sans actualisation) :
+//! gain g(s) = lim 1/T E[Σ_t r_t] et biais h(s), solution de h + g = r_π + P_π h.
+//!
+//! Les états terminaux sont traités comme absorbants, de récompense nulle.
+
+use crate::algorithms::dp::linalg::solve;
+use crate::algorithms::dp::sweeps::{greedy_policy, q_value, terminal_mask};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use std::fmt::{Display, Formatter};
+
+/// Poids de la transformation d'apériodicité de la value iteration relative :
+/// P_τ = τ P + (1 - τ) I a le même gain et le même biais que P, mais n'est jamais périodique
+const APERIODICITY: f64 = 0.5;
+/// Tolérance de comparaison des actions dans l'amélioration de la policy
+const IMPROVEMENT_EPS: f64 = 1e-9;
+
+/// Structure de la chaîne de Markov induite par une policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainStructure {
+    /// Une seule classe récurrente (plus d'éventuels états transitoires) : le gain est constant
+    Unichain,
+    /// Plusieurs classes récurrentes : le gain dépend de l'état de départ
+    Multichain { recurrent_classes: usize },
+}
+
+impl Display for ChainStructure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainStructure::Unichain => write!(f, "unichain"),
+            ChainStructure::Multichain { recurrent_classes } => {
+                write!(f, "multichain ({} classes récurrentes)", recurrent_classes)
+            }
+        }
+    }
+}
+
+/// Gain et biais exacts d'une policy
+#[derive(Debug, Clone)]
+pub struct AverageRewardEvaluation {
+    pub gain: Vec<f64>,
+    /// Biais normalisé par P* h = 0 (moyenne nulle sous la loi stationnaire de chaque classe)
+    pub bias: Vec<f64>,
+    pub recurrent_classes: Vec<Vec<usize>>,
+    pub structure: ChainStructure,
+}
+
+/// Résultat des solveurs en récompense moyenne
+#[derive(Debug)]
+pub struct AverageRewardSolution {
+    pub policy: DeterministicPolicy,
+    pub gain: Vec<f64>,
+    /// Biais (policy iteration) ou valeurs relatives nulles à l'état de référence
+    /// (value iteration relative) : les deux coïncident à une constante près en unichain
+    pub bias: Vec<f64>,
+    /// Structure de la chaîne induite par `policy`
+    pub structure: ChainStructure,
+    /// Balayages (value iteration relative) ou itérations de policy iteration
+    pub iterations: usize,
+}
+
+impl AverageRewardSolution {
+    /// Gain commun à tous les états, si la policy est unichain
+    pub fn unichain_gain(&self) -> Option<f64> {
+        match self.structure {
+            ChainStructure::Unichain => self.gain.first().copied(),
+            ChainStructure::Multichain { .. } => None,
+        }
+    }
+}
+
+/// Chaîne induite par `policy` : transitions fusionnées par état d'arrivée et récompense moyenne
+fn policy_chain(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &DeterministicPolicy,
+    is_terminal: &[bool],
+) -> (Vec<Vec<(usize, f64)>>, Vec<f64>) {
+    let mut transitions = vec![Vec::new(); env.num_states()];
+    let mut rewards = vec![0.0; env.num_states()];
+    for s in 0..env.num_states() {
+        if is_terminal[s] {
+            transitions[s].push((s, 1.0));
+            continue;
+        }
+        let row: &mut Vec<(usize, f64)> = &mut transitions[s];
+        for (s_prime, r_index, p) in env.transitions_from(s, policy.policy_table[s]) {
+            rewards[s] += p * env.get_reward(r_index);
+            match row.iter_mut().find(|(t, _)| *t == s_prime) {
+                Some((_, q)) => *q += p,
+                None => row.push((s_prime, p)),
+            }
+        }
+    }
+    (transitions, rewards)
+}
+
+/// Composantes fortement connexes (Tarjan, version itérative)
+fn strongly_connected_components(graph: &[Vec<(usize, f64)>]) -> Vec<Vec<usize>> {
+    let n = graph.len();
+    let mut index = vec![usize::MAX; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut counter = 0;
+
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // (sommet, prochain arc à explorer)
+        let mut calls = vec![(root, 0)];
+        index[root] = counter;
+        lowlink[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(v, edge)) = calls.last() {
+            if let Some(&(w, _)) = graph[v].get(edge) {
+                calls.last_mut().unwrap().1 += 1;
+                if index[w] == usize::MAX {
+                    index[w] = counter;
+                    lowlink[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    lowlink[v] = lowlink[v].min(index[w]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(u, _)) = calls.last() {
+                lowlink[u] = lowlink[u].min(lowlink[v]);
+            }
+            if lowlink[v] == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Classes récurrentes : composantes fortement connexes dont on ne sort pas
+fn recurrent_classes(transitions: &[Vec<(usize, f64)>]) -> Vec<Vec<usize>> {
+    let mut classes: Vec<Vec<usize>> = strongly_connected_components(transitions)
+        .into_iter()
+        .filter(|component| {
+            component.iter().all(|&s| {
+                transitions[s]
+                    .iter()
+                    .all(|&(t, p)| p == 0.0 || component.binary_search(&t).is_ok())
+            })
+        })
+        .collect();
+    classes.sort_unstable();
+    classes
+}
+
+fn structure_of(classes: &[Vec<usize>]) -> ChainStructure {
+    match classes.len() {
+        1 => ChainStructure::Unichain,
+        n => ChainStructure::Multichain { recurrent_classes: n },
+    }
+}
+
+/// Structure unichain / multichain de la chaîne induite par `policy`
+pub fn chain_structure(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &DeterministicPolicy,
+) -> ChainStructure {
+    let (transitions, _) = policy_chain(env, policy, &terminal_mask(env));
+    structure_of(&recurrent_classes(&transitions))
+}
+
+/// Gain et biais exacts de `policy`, par résolution de systèmes linéaires denses : loi
+/// stationnaire et biais de chaque classe récurrente, puis gain et biais des états transitoires.
+pub fn evaluate_average_reward(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &DeterministicPolicy,
+) -> AverageRewardEvaluation {
+    let n = env.num_states();
+    let (transitions, rewards) = policy_chain(env, policy, &terminal_mask(env));
+    let classes = recurrent_classes(&transitions);
+    let mut gain = vec![0.0; n];
+    let mut bias = vec![0.0; n];
+    let mut local = vec![usize::MAX; n];
+    let mut recurrent = vec![false; n];
+
+    for class in &classes {
+        let m = class.len();
+        for (i, &s) in class.iter().enumerate() {
+            local[s] = i;
+            recurrent[s] = true;
+        }
+
+        // Loi stationnaire : π (P - I) = 0, la dernière équation remplacée par Σ π = 1
+        let mut a = vec![vec![0.0; m]; m];
+        for (i, &s) in class.iter().enumerate() {
+            a[i][i] -= 1.0;
+            for &(t, p) in &transitions[s] {
+                a[local[t]][i] += p;
+            }
+        }
+        a[m - 1] = vec![1.0; m];
+        let mut b = vec![0.0; m];
+        b[m - 1] = 1.0;
+        let stationary = solve(a, b).expect("Loi stationnaire introuvable");
+        let g: f64 = class.iter().zip(&stationary).map(|(&s, pi)| pi * rewards[s]).sum();
+
+        // Biais : h - P h = r - g, avec h = 0 au premier état, puis recentré pour que π h = 0
+        let mut a = vec![vec![0.0; m]; m];
+        let mut b = vec![0.0; m];
+        for (i, &s) in class.iter().enumerate() {
+            a[i][i] += 1.0;
+            for &(t, p) in &transitions[s] {
+                a[i][local[t]] -= p;
+            }
+            b[i] = rewards[s] - g;
+        }
+        a[0] = vec![0.0; m];
+        a[0][0] = 1.0;
+        b[0] = 0.0;
+        let h = solve(a, b).expect("Biais de la classe récurrente introuvable");
+        let mean: f64 = h.iter().zip(&stationary).map(|(h, pi)| h * pi).sum();
+        for (i, &s) in class.iter().enumerate() {
+            gain[s] = g;
+            bias[s] = h[i] - mean;
+        }
+    }
+
+    // États transitoires : g_T = P_TT g_T + P_TC g_C et h_T = r_T - g_T + P_TT h_T + P_TC h_C
+    let transient: Vec<usize> = (0..n).filter(|&s| !recurrent[s]).collect();
+    if !transient.is_empty() {
+        let m = transient.len();
+        for (i, &s) in transient.iter().enumerate() {
+            local[s] = i;
+        }
+        let mut a = vec![vec![0.0; m]; m];
+        let mut b_gain = vec![0.0; m];
+        for (i, &s) in transient.iter().enumerate() {
+            a[i][i] += 1.0;
+            for &(t, p) in &transitions[s] {
+                if recurrent[t] {
+                    b_gain[i] += p * gain[t];
+                } else {
+                    a[i][local[t]] -= p;
+                }
+            }
+        }
+        let g_t = solve(a.clone(), b_gain).expect("Gain des états transitoires introuvable");
+        let b_bias = transient
+            .iter()
+            .zip(&g_t)
+            .map(|(&s, g)| {
+                let to_recurrent: f64 = transitions[s]
+                    .iter()
+                    .filter(|&&(t, _)| recurrent[t])
+                    .map(|&(t, p)| p * bias[t])
+                    .sum();
+                rewards[s] - g + to_recurrent
+            })
+            .collect();
+        let h_t = solve(a, b_bias).expect("Biais des états transitoires introuvable");
+        for (i, &s) in transient.iter().enumerate() {
+            gain[s] = g_t[i];
+            bias[s] = h_t[i];
+        }
+    }
+
+    AverageRewardEvaluation {
+        gain,
+        bias,
+        structure: structure_of(&classes),
+        recurrent_classes: classes,
+    }
+}
+
+/// Value iteration relative : h <- T h - (T h)(s_ref), sur la chaîne rendue apériodique.
+/// S'arrête quand la semi-norme span(T h - h) passe sous `theta` ; le gain est alors encadré
+/// par min et max de T h - h, dont on renvoie le milieu (le même pour tous les états).
+///
+/// Le gain n'a de sens que si la policy obtenue est unichain (voir `structure`) ; en
+/// multichain, le span ne tend pas vers 0 et il faut passer par la policy iteration.
+pub fn relative_value_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    max_iter: usize,
+) -> AverageRewardSolution {
+    let is_terminal = terminal_mask(env);
+    let reference = (0..env.num_states()).find(|&s| !is_terminal[s]).unwrap_or(0);
+    let mut h = vec![0.0; env.num_states()];
+    let mut gain = 0.0;
+    let mut iterations = 0;
+
+    for _ in 0..max_iter {
+        iterations += 1;
+        let backup: Vec<f64> = (0..env.num_states())
+            .map(|s| {
+                if is_terminal[s] {
+                    return h[s];
+                }
+                let best = (0..env.num_actions())
+                    .map(|a| q_value(env, s, a, &h, 1.0))
+                    .fold(f64::NEG_INFINITY, f64::max);
+                (1.0 - APERIODICITY) * h[s] + APERIODICITY * best
+            })
+            .collect();
+
+        let (lo, hi) = backup
+            .iter()
+            .zip(&h)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (w, v)| {
+                (lo.min(w - v), hi.max(w - v))
+            });
+        gain = (lo + hi) / 2.0 / APERIODICITY;
+        let offset = backup[reference];
+        h = backup.iter().map(|w| w - offset).collect();
+        if hi - lo < theta {
+            break;
+        }
+    }
+
+    let policy = greedy_policy(env, &h, &is_terminal, 1.0);
+    let structure = chain_structure(env, &policy);
+    AverageRewardSolution {
+        policy,
+        gain: vec![gain; env.num_states()],
+        bias: h,
+        structure,
+        iterations,
+    }
+}
+
+/// Policy iteration en récompense moyenne (Howard, version multichain) : évaluation exacte du
+/// gain et du biais, puis amélioration sur le gain (max_a Σ P g) et, à gain égal, sur le biais
+/// (max_a r + Σ P h). L'action courante est gardée en cas d'égalité, ce qui assure l'arrêt.
+/// En unichain, la première étape ne change jamais rien.
+pub fn average_reward_policy_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    max_iter: usize,
+) -> AverageRewardSolution {
+    let is_terminal = terminal_mask(env);
+    let mut policy = DeterministicPolicy::from_vec(env, vec![0; env.num_states()]);
+    let mut evaluation = evaluate_average_reward(env, &policy);
+    let mut iterations = 0;
+
+    while iterations < max_iter {
+        iterations += 1;
+        let expected_gain = |s: usize, a: usize| -> f64 {
+            env.transitions_from(s, a)
+                .into_iter()
+                .map(|(t, _, p)| p * evaluation.gain[t])
+                .sum()
+        };
+
+        // Étape 1 : amélioration du gain
+        let mut table = policy.policy_table.clone();
+        let mut changed = false;
+        let mut gain_optimal: Vec<Vec<usize>> = vec![Vec::new(); env.num_states()];
+        for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+            let gains: Vec<f64> = (0..env.num_actions()).map(|a| expected_gain(s, a)).collect();
+            let best = gains.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if gains[table[s]] < best - IMPROVEMENT_EPS {
+                table[s] = gains.iter().position(|&g| g == best).unwrap();
+                changed = true;
+            }
+            gain_optimal[s] = (0..env.num_actions())
+                .filter(|&a| gains[a] >= best - IMPROVEMENT_EPS)
+                .collect();
+        }
+
+        // Étape 2 : à gain optimal, amélioration du biais
+        if !changed {
+            for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+                let values: Vec<(usize, f64)> = gain_optimal[s]
+                    .iter()
+                    .map(|&a| (a, q_value(env, s, a, &evaluation.bias, 1.0)))
+                    .collect();
+                let best = values.iter().fold((table[s], f64::NEG_INFINITY), |best, &(a, q)| {
+                    if q > best.1 {
+                        (a, q)
+                    } else {
+                        best
+                    }
+                });
+                let current = q_value(env, s, table[s], &evaluation.bias, 1.0);
+                if current < best.1 - IMPROVEMENT_EPS {
+                    table[s] = best.0;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+        policy = DeterministicPolicy::from_vec(env, table);
+        evaluation = evaluate_average_reward(env, &policy);
+    }
+
+    AverageRewardSolution {
+        policy,
+        gain: evaluation.gain,
+        bias: evaluation.bias,
+        structure: evaluation.structure,
+        iterations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::envs::DPEnvironment;
+
+    /// Deux états, sans état terminal. Action 0 : rester (récompense 1 en 0, 2 en 1),
+    /// action 1 : changer d'état (récompense 0).
+    fn two_rooms() -> DPEnvironment {
+        let mut env = DPEnvironment::new(2, 2, 3, vec![0.0, 1.0, 2.0], vec![]);
+        env.set_transition_prob(0, 0, 0, 1, 1.0);
+        env.set_transition_prob(0, 1, 1, 0, 1.0);
+        env.set_transition_prob(1, 0, 1, 2, 1.0);
+        env.set_transition_prob(1, 1, 0, 0, 1.0);
+        env
+    }
+
+    #[test]
+    fn test_multichain_policy_is_detected_and_evaluated() {
+        let env = two_rooms();
+        // Rester partout : deux classes récurrentes, gains différents
+        let stay = DeterministicPolicy::from_vec(&env, vec![0, 0]);
+        let evaluation = evaluate_average_reward(&env, &stay);
+        assert_eq!(evaluation.structure, ChainStructure::Multichain { recurrent_classes: 2 });
+        assert_eq!(evaluation.gain, vec![1.0, 2.0]);
+        assert_eq!(evaluation.bias, vec![0.0, 0.0]);
+
+        // Aller de 0 à 1 puis y rester : 0 est transitoire, h(0) = 0 - 2 + h(1)
+        let go = DeterministicPolicy::from_vec(&env, vec![1, 0]);
+        let evaluation = evaluate_average_reward(&env, &go);
+        assert_eq!(evaluation.structure, ChainStructure::Unichain);
+        assert_eq!(evaluation.recurrent_classes, vec![vec![1]]);
+        assert_eq!(evaluation.gain, vec![2.0, 2.0]);
+        assert_eq!(evaluation.bias, vec![-2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_policy_iteration_leaves_the_worse_recurrent_class() {
+        let solution = average_reward_policy_iteration(&two_rooms(), 100);
+        assert_eq!(solution.policy.policy_table, vec![1, 0]);
+        assert_eq!(solution.structure, ChainStructure::Unichain);
+        assert_eq!(solution.unichain_gain(), Some(2.0));
+        assert_eq!(solution.bias, vec![-2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_relative_value_iteration_on_periodic_chain() {
+        // 0 -> 1 -> 2 -> 0 (récompenses 3, 0, 0) ou raccourci 0 -> 0 (récompense 0.9) :
+        // le cycle, périodique, rapporte 1 par pas en moyenne
+        let mut env = DPEnvironment::new(3, 2, 3, vec![0.0, 0.9, 3.0], vec![]);
+        env.set_transition_prob(0, 0, 1, 2, 1.0);
+        env.set_transition_prob(0, 1, 0, 1, 1.0);
+        for a in 0..2 {
+            env.set_transition_prob(1, a, 2, 0, 1.0);
+            env.set_transition_prob(2, a, 0, 0, 1.0);
+        }
+
+        let rvi = relative_value_iteration(&env, 1e-10, 10_000);
+        let pi = average_reward_policy_iteration(&env, 100);
+        assert_eq!(rvi.structure, ChainStructure::Unichain);
+        assert_eq!(rvi.policy.policy_table[0], 0);
+        assert_eq!(pi.policy.policy_table[0], 0);
+        assert!((rvi.unichain_gain().unwrap() - 1.0).abs() < 1e-8);
+        assert!((pi.unichain_gain().unwrap() - 1.0).abs() < 1e-12);
+
+        // Valeurs relatives et biais ne diffèrent que d'une constante
+        let shift = pi.bias[0] - rvi.bias[0];
+        for (h, b) in rvi.bias.iter().zip(&pi.bias) {
+            assert!((h + shift - b).abs() < 1e-6);
+        }
+    }
+}
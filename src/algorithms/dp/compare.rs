@@ -0,0 +1,297 @@
+use crate::algorithms::dp::exact_evaluation::exact_policy_evaluation;
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::Policy;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+/// Nombre maximal de balayages de l'évaluation, au cas où une policy boucle avec gamma = 1
+const MAX_SWEEPS: usize = 100_000;
+
+/// Au-delà, le système dense de l'évaluation exacte devient trop coûteux : on itère
+const EXACT_EVALUATION_MAX_STATES: usize = 2_000;
+
+/// Différences entre une policy candidate et une policy de référence (en général l'optimum
+/// de value/policy iteration) sur le même modèle
+#[derive(Debug)]
+pub struct PolicyComparison {
+    /// (état, action candidate, action de référence) pour les états non terminaux où elles diffèrent
+    pub differing_states: Vec<(usize, usize, usize)>,
+    pub candidate_values: Vec<f64>,
+    pub reference_values: Vec<f64>,
+    /// V_ref(s) - V_cand(s)
+    pub value_loss: Vec<f64>,
+    /// États non terminaux atteignables depuis les états de départ en suivant la référence
+    pub reachable_states: Vec<usize>,
+    /// Part des états atteignables où les deux policies choisissent la même action
+    pub agreement_rate: f64,
+    /// Faux si l'évaluation itérative d'une des policies s'est arrêtée sur `MAX_SWEEPS` :
+    /// les valeurs et les pertes ne sont alors pas fiables
+    pub converged: bool,
+}
+
+impl PolicyComparison {
+    pub fn max_value_loss(&self) -> f64 {
+        self.value_loss.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Perte de valeur moyenne sur les états atteignables
+    pub fn mean_reachable_loss(&self) -> f64 {
+        if self.reachable_states.is_empty() {
+            return 0.0;
+        }
+        self.reachable_states
+            .iter()
+            .map(|&s| self.value_loss[s])
+            .sum::<f64>()
+            / self.reachable_states.len() as f64
+    }
+}
+
+impl Display for PolicyComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.converged {
+            writeln!(f, "ATTENTION : évaluation non convergée, valeurs approximatives")?;
+        }
+        writeln!(f, "États où les actions diffèrent : {}", self.differing_states.len())?;
+        for &(s, a_cand, a_ref) in &self.differing_states {
+            writeln!(
+                f,
+                "  état {:>6} : candidate {} / référence {} (perte {:.6})",
+                s, a_cand, a_ref, self.value_loss[s]
+            )?;
+        }
+        writeln!(f, "Perte de valeur max : {:.6}", self.max_value_loss())?;
+        writeln!(
+            f,
+            "Perte moyenne sur les {} états atteignables : {:.6}",
+            self.reachable_states.len(),
+            self.mean_reachable_loss()
+        )?;
+        write!(
+            f,
+            "Accord sur les états atteignables : {:.2}%",
+            self.agreement_rate * 100.0
+        )
+    }
+}
+
+/// Action la plus probable de la policy dans l'état
+fn main_action(policy: &dyn Policy, state: usize) -> usize {
+    let probs = policy.action_probs(state, &vec![true; policy.num_actions()]);
+    let mut best = 0;
+    for (a, &p) in probs.iter().enumerate() {
+        if p > probs[best] {
+            best = a;
+        }
+    }
+    best
+}
+
+/// V^π par évaluation itérative, pour une policy déterministe ou stochastique.
+/// Le booléen est faux si l'écart était encore au-dessus de `theta` après `MAX_SWEEPS` balayages.
+pub fn evaluate_policy_values(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &dyn Policy,
+    theta: f64,
+    gamma: f64,
+) -> (Vec<f64>, bool) {
+    let num_states = env.num_states();
+    let mut is_terminal = vec![false; num_states];
+    for ts in env.get_terminal_states() {
+        is_terminal[ts] = true;
+    }
+    let all_actions = vec![true; env.num_actions()];
+    let probs: Vec<Vec<f64>> = (0..num_states)
+        .map(|s| policy.action_probs(s, &all_actions))
+        .collect();
+
+    let mut values = vec![0.0; num_states];
+    for _ in 0..MAX_SWEEPS {
+        let mut delta: f64 = 0.0;
+        for s in 0..num_states {
+            if is_terminal[s] {
+                continue;
+            }
+            let mut total = 0.0;
+            for (a, &pi) in probs[s].iter().enumerate() {
+                if pi == 0.0 {
+                    continue;
+                }
+                for (s_prime, r_index, p) in env.transitions_from(s, a) {
+                    total += pi * p * (env.get_reward(r_index) + gamma * values[s_prime]);
+                }
+            }
+            delta = delta.max((values[s] - total).abs());
+            values[s] = total;
+        }
+        if delta < theta {
+            return (values, true);
+        }
+    }
+    (values, false)
+}
+
+/// V^π exacte si le modèle est assez petit et le système inversible, itérative sinon
+fn policy_values(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &dyn Policy,
+    theta: f64,
+    gamma: f64,
+) -> (Vec<f64>, bool) {
+    if env.num_states() <= EXACT_EVALUATION_MAX_STATES {
+        if let Some(evaluation) = exact_policy_evaluation(env, policy, gamma) {
+            return (evaluation.values, true);
+        }
+    }
+    evaluate_policy_values(env, policy, theta, gamma)
+}
+
+/// États non terminaux atteignables depuis `start_states` en suivant `policy`
+fn reachable_states(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &dyn Policy,
+    start_states: &[usize],
+    is_terminal: &[bool],
+) -> Vec<usize> {
+    let all_actions = vec![true; env.num_actions()];
+    let mut seen = vec![false; env.num_states()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for &s in start_states {
+        if !seen[s] {
+            seen[s] = true;
+            queue.push_back(s);
+        }
+    }
+
+    let mut reachable = Vec::new();
+    while let Some(s) = queue.pop_front() {
+        if is_terminal[s] {
+            continue;
+        }
+        reachable.push(s);
+        for (a, pi) in policy.action_probs(s, &all_actions).into_iter().enumerate() {
+            if pi == 0.0 {
+                continue;
+            }
+            for (s_prime, _, _) in env.transitions_from(s, a) {
+                if !seen[s_prime] {
+                    seen[s_prime] = true;
+                    queue.push_back(s_prime);
+                }
+            }
+        }
+    }
+    reachable.sort_unstable();
+    reachable
+}
+
+/// Compare `candidate` à `reference` sur le modèle `env`.
+/// `start_states` sert à déterminer les états atteignables sous la référence.
+/// Les valeurs sont calculées exactement (voir `exact_policy_evaluation`) ; `theta` ne sert
+/// qu'aux grands modèles et aux policies dont le système est singulier, évalués par itération.
+pub fn compare_policies(
+    env: &dyn DynamicProgramingEnvironment,
+    candidate: &dyn Policy,
+    reference: &dyn Policy,
+    start_states: &[usize],
+    theta: f64,
+    gamma: f64,
+) -> PolicyComparison {
+    assert_eq!(candidate.num_actions(), env.num_actions());
+    assert_eq!(reference.num_actions(), env.num_actions());
+
+    let mut is_terminal = vec![false; env.num_states()];
+    for ts in env.get_terminal_states() {
+        is_terminal[ts] = true;
+    }
+
+    let (candidate_values, candidate_converged) = policy_values(env, candidate, theta, gamma);
+    let (reference_values, reference_converged) = policy_values(env, reference, theta, gamma);
+    let value_loss: Vec<f64> = reference_values
+        .iter()
+        .zip(&candidate_values)
+        .map(|(v_ref, v_cand)| v_ref - v_cand)
+        .collect();
+
+    let differing_states: Vec<(usize, usize, usize)> = (0..env.num_states())
+        .filter(|&s| !is_terminal[s])
+        .map(|s| (s, main_action(candidate, s), main_action(reference, s)))
+        .filter(|&(_, a_cand, a_ref)| a_cand != a_ref)
+        .collect();
+
+    let reachable_states = reachable_states(env, reference, start_states, &is_terminal);
+    let agreement_rate = if reachable_states.is_empty() {
+        1.0
+    } else {
+        let agree = reachable_states
+            .iter()
+            .filter(|&&s| main_action(candidate, s) == main_action(reference, s))
+            .count();
+        agree as f64 / reachable_states.len() as f64
+    };
+
+    PolicyComparison {
+        differing_states,
+        candidate_values,
+        reference_values,
+        value_loss,
+        reachable_states,
+        agreement_rate,
+        converged: candidate_converged && reference_converged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::value_iteration::value_iteration;
+    use crate::core::envs::DPEnvironment;
+    use crate::core::policies::DeterministicPolicy;
+    use crate::environments::line_world::line_world_dp;
+
+    #[test]
+    fn test_compare_with_optimal_policy() {
+        let env = line_world_dp();
+        let (optimal, v_opt, _) = value_iteration(&env, 1e-8, 0.9, 1000);
+
+        let identical = compare_policies(&env, &optimal, &optimal, &[2], 1e-8, 0.9);
+        assert!(identical.differing_states.is_empty());
+        assert_eq!(identical.agreement_rate, 1.0);
+        assert_eq!(identical.max_value_loss(), 0.0);
+
+        // Candidate qui va à gauche depuis l'état 1 : perd 1.9 en 1
+        let candidate = DeterministicPolicy::from_vec(&env, vec![0, 0, 1, 1, 0]);
+        let cmp = compare_policies(&env, &candidate, &optimal, &[2], 1e-8, 0.9);
+        assert_eq!(cmp.differing_states, vec![(1, 0, 1)]);
+        assert_eq!(cmp.reachable_states, vec![2, 3]);
+        assert_eq!(cmp.agreement_rate, 1.0);
+        assert!((cmp.reference_values[1] - v_opt[1]).abs() < 1e-6);
+        assert!((cmp.value_loss[1] - 1.81).abs() < 1e-6);
+        assert!(cmp.value_loss[2].abs() < 1e-9);
+
+        // Depuis l'état 1, la divergence devient atteignable
+        let cmp = compare_policies(&env, &candidate, &optimal, &[1], 1e-8, 0.9);
+        assert_eq!(cmp.reachable_states, vec![1, 2, 3]);
+        assert!((cmp.agreement_rate - 2.0 / 3.0).abs() < 1e-12);
+        assert!(cmp.converged);
+    }
+
+    #[test]
+    fn test_non_convergence_is_reported() {
+        // Boucle sans fin à +1 par pas : V^π diverge avec gamma = 1
+        let mut env = DPEnvironment::new(1, 1, 1, vec![1.0], vec![]);
+        env.set_transition_prob(0, 0, 0, 0, 1.0);
+        let policy = DeterministicPolicy::from_vec(&env, vec![0]);
+
+        let (_, converged) = evaluate_policy_values(&env, &policy, 1e-8, 1.0);
+        assert!(!converged);
+        let cmp = compare_policies(&env, &policy, &policy, &[0], 1e-8, 1.0);
+        assert!(!cmp.converged);
+        assert!(cmp.to_string().starts_with("ATTENTION"));
+
+        // Avec gamma < 1, le système est inversible : V = 1 / (1 - gamma)
+        let cmp = compare_policies(&env, &policy, &policy, &[0], 1e-8, 0.5);
+        assert!(cmp.converged);
+        assert!((cmp.reference_values[0] - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,127 @@
+//! Évaluation exacte d'une policy : résolution directe de (I - γ P_π) V = R_π, sans itérer
+//! jusqu'à convergence (ce qui devient très lent quand γ est proche de 1).
+
+use crate::algorithms::dp::linalg::solve;
+use crate::algorithms::dp::sweeps::{q_value, terminal_mask};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::Policy;
+use crate::core::q_table::QTable;
+
+/// V^π et Q^π d'une policy
+#[derive(Debug)]
+pub struct PolicyEvaluation {
+    pub values: Vec<f64>,
+    /// Q^π(s, a) = Σ P(s', r | s, a) [r + γ V^π(s')], nul dans les états terminaux
+    pub q: QTable,
+}
+
+/// Évalue exactement `policy`, déterministe ou stochastique, par élimination de Gauss sur les
+/// états non terminaux (les terminaux valent 0). Le système est dense : à réserver aux modèles
+/// de quelques milliers d'états au plus.
+///
+/// Renvoie `None` si le système est singulier, ce qui n'arrive qu'avec γ = 1 et une policy qui
+/// peut ne jamais atteindre d'état terminal.
+pub fn exact_policy_evaluation(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &dyn Policy,
+    gamma: f64,
+) -> Option<PolicyEvaluation> {
+    let is_terminal = terminal_mask(env);
+    let states: Vec<usize> = (0..env.num_states()).filter(|&s| !is_terminal[s]).collect();
+    let mut row_of = vec![usize::MAX; env.num_states()];
+    for (i, &s) in states.iter().enumerate() {
+        row_of[s] = i;
+    }
+
+    let all_actions = vec![true; env.num_actions()];
+    let n = states.len();
+    let mut a = vec![vec![0.0; n]; n];
+    let mut b = vec![0.0; n];
+    for (i, &s) in states.iter().enumerate() {
+        a[i][i] += 1.0;
+        for (action, pi) in policy.action_probs(s, &all_actions).into_iter().enumerate() {
+            if pi == 0.0 {
+                continue;
+            }
+            for (s_prime, r_index, p) in env.transitions_from(s, action) {
+                b[i] += pi * p * env.get_reward(r_index);
+                if !is_terminal[s_prime] {
+                    a[i][row_of[s_prime]] -= gamma * pi * p;
+                }
+            }
+        }
+    }
+
+    let solution = solve(a, b)?;
+    let mut values = vec![0.0; env.num_states()];
+    for (&s, v) in states.iter().zip(solution) {
+        values[s] = v;
+    }
+
+    let mut q = QTable::dense(env.num_states(), env.num_actions());
+    for &s in &states {
+        for action in 0..env.num_actions() {
+            q.set(s, action, q_value(env, s, action, &values, gamma));
+        }
+    }
+    Some(PolicyEvaluation { values, q })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::compare::evaluate_policy_values;
+    use crate::algorithms::dp::policy_iteration::policy_evaluation;
+    use crate::core::policies::{DeterministicPolicy, ProbabilisticPolicy};
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+    use crate::environments::line_world::line_world_dp;
+
+    #[test]
+    fn test_deterministic_policy_matches_iterative_evaluation() {
+        let env = grid_world();
+        // Bas jusqu'à la dernière ligne, puis droite
+        let table = (0..25).map(|s| if s < 20 { 1 } else { 3 }).collect();
+        let policy = DeterministicPolicy::from_vec(&env, table);
+        let exact = exact_policy_evaluation(&env, &policy, 0.9999).unwrap();
+        let iterative = policy_evaluation(&env, &policy, 1e-12, 0.9999);
+        for (v, w) in exact.values.iter().zip(&iterative) {
+            assert!((v - w).abs() < 1e-8);
+        }
+        // Q^π(s, π(s)) = V^π(s)
+        for s in 0..25 {
+            let q = exact.q.get(s, policy.policy_table[s]);
+            assert!((q - exact.values[s]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_uniform_random_walk_without_discount() {
+        let env = line_world_dp();
+        let uniform = ProbabilisticPolicy::uniform(5, 2);
+        let exact = exact_policy_evaluation(&env, &uniform, 1.0).unwrap();
+        // Marche aléatoire entre -1 (à gauche) et +1 (à droite)
+        for (v, e) in exact.values.iter().zip([0.0, -0.5, 0.0, 0.5, 0.0]) {
+            assert!((v - e).abs() < 1e-12);
+        }
+        for s in 1..4 {
+            let mean = 0.5 * (exact.q.get(s, 0) + exact.q.get(s, 1));
+            assert!((mean - exact.values[s]).abs() < 1e-12);
+        }
+
+        let (iterative, converged) = evaluate_policy_values(&env, &uniform, 1e-12, 0.9);
+        assert!(converged);
+        let discounted = exact_policy_evaluation(&env, &uniform, 0.9).unwrap();
+        for (v, w) in discounted.values.iter().zip(&iterative) {
+            assert!((v - w).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_policy_that_never_terminates_is_singular() {
+        let env = grid_world();
+        // Toujours vers le haut : les cases de la première ligne bouclent sur elles-mêmes
+        let policy = DeterministicPolicy::from_vec(&env, vec![0; 25]);
+        assert!(exact_policy_evaluation(&env, &policy, 1.0).is_none());
+        assert!(exact_policy_evaluation(&env, &policy, 0.99).is_some());
+    }
+}
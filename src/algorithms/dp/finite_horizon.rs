@@ -0,0 +1,96 @@
+//! Induction arrière sur un horizon fini : la policy optimale dépend du nombre de pas restants.
+
+use crate::algorithms::dp::sweeps::{q_value, terminal_mask};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::NonStationaryPolicy;
+
+/// Induction arrière sur `horizon` pas, sans actualisation :
+/// V_H = 0, puis V_t(s) = max_a Σ P(s', r | s, a) [r + V_{t+1}(s')] pour t = H - 1, ..., 0.
+///
+/// Renvoie π_t(s) pour t < H (la première action maximale en cas d'égalité) et `values[t][s]`
+/// = V_t(s) pour t ≤ H. Les états terminaux gardent une valeur nulle et l'action 0.
+pub fn finite_horizon_dp(
+    env: &dyn DynamicProgramingEnvironment,
+    horizon: usize,
+) -> (NonStationaryPolicy, Vec<Vec<f64>>) {
+    assert!(horizon >= 1, "L'horizon doit compter au moins un pas");
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![vec![0.0; env.num_states()]; horizon + 1];
+    let mut tables = vec![vec![0; env.num_states()]; horizon];
+
+    for t in (0..horizon).rev() {
+        let (current, next) = values.split_at_mut(t + 1);
+        let (v_t, v_next) = (&mut current[t], &next[0]);
+        for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+            let mut best = (0, f64::NEG_INFINITY);
+            for a in 0..env.num_actions() {
+                let q = q_value(env, s, a, v_next, 1.0);
+                if q > best.1 {
+                    best = (a, q);
+                }
+            }
+            tables[t][s] = best.0;
+            v_t[s] = best.1;
+        }
+    }
+
+    let policy = NonStationaryPolicy::new(env.num_states(), env.num_actions(), tables);
+    (policy, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::value_iteration::value_iteration;
+    use crate::core::envs::{DPEnvironment, DynamicProgramingEnvironment};
+    use crate::core::policies::Policy;
+    use crate::environments::line_world::line_world_dp;
+    use crate::environments::pierre_feuille_ciseaux::pierre_feuille_ciseaux_dp;
+
+    /// État 0 : « encaisser » (+1, fin) ou « attendre » (+0.6, reste en 0)
+    fn cash_or_wait() -> DPEnvironment {
+        let mut env = DPEnvironment::new(2, 2, 3, vec![0.0, 0.6, 1.0], vec![1]);
+        env.set_transition_prob(0, 0, 1, 2, 1.0);
+        env.set_transition_prob(0, 1, 0, 1, 1.0);
+        env
+    }
+
+    #[test]
+    fn test_policy_depends_on_remaining_steps() {
+        let (policy, values) = finite_horizon_dp(&cash_or_wait(), 3);
+        // On attend tant qu'il reste au moins deux pas, on encaisse au dernier
+        assert_eq!(policy.get_action_at(&0, 0), 1);
+        assert_eq!(policy.get_action_at(&0, 1), 1);
+        assert_eq!(policy.get_action_at(&0, 2), 0);
+        assert_eq!(policy.get_action(&0), 1);
+        let v0: Vec<f64> = values.iter().map(|v| v[0]).collect();
+        let expected = [2.2, 1.6, 1.0, 0.0];
+        for (v, e) in v0.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_short_horizon_on_line_world() {
+        let (policy, values) = finite_horizon_dp(&line_world_dp(), 3);
+        // Depuis 1, la case gagnante est à 3 pas : atteinte avec H = 3 seulement
+        assert_eq!(values[0][1], 1.0);
+        assert_eq!(values[1][1], 0.0);
+        // Avec un seul pas restant depuis 1, la gauche perd : on va à droite
+        assert_eq!(values[2][1], 0.0);
+        assert_eq!(policy.get_action_at(&1, 2), 1);
+    }
+
+    #[test]
+    fn test_matches_value_iteration_on_fixed_horizon_game() {
+        // Pierre-feuille-ciseaux en deux manches : l'horizon est encodé dans les états
+        let env = pierre_feuille_ciseaux_dp();
+        let (vi_policy, vi_values, _) = value_iteration(&env, 1e-12, 1.0, 100);
+        let (policy, values) = finite_horizon_dp(&env, 2);
+        assert!((values[0][0] - vi_values[0]).abs() < 1e-12);
+        for s in 1..4 {
+            assert_eq!(policy.get_action_at(&s, 1), vi_policy.policy_table[s]);
+            assert!((values[1][s] - vi_values[s]).abs() < 1e-12);
+        }
+    }
+}
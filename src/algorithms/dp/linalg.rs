@@ -0,0 +1,52 @@
+//! Algèbre linéaire dense minimale pour les évaluations exactes de policies.
+
+/// Résout `a x = b` par élimination de Gauss avec pivot partiel.
+/// Renvoie `None` si la matrice est (numériquement) singulière.
+pub(crate) fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    debug_assert!(a.len() == n && a.iter().all(|row| row.len() == n));
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            if factor != 0.0 {
+                for (x, &p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *x -= factor * p;
+                }
+                b[col + 1 + offset] -= factor * b[col];
+            }
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|j| a[i][j] * x[j]).sum();
+        x[i] = (b[i] - sum) / a[i][i];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_with_pivoting() {
+        // Le premier pivot est nul : il faut échanger les lignes
+        let a = vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![2.0, 0.0, 3.0]];
+        let x = solve(a, vec![7.0, 3.0, 11.0]).unwrap();
+        for (x, e) in x.iter().zip([1.0, 2.0, 3.0]) {
+            assert!((x - e).abs() < 1e-12, "{:?}", x);
+        }
+        assert!(solve(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![1.0, 2.0]).is_none());
+    }
+}
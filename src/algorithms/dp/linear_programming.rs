@@ -0,0 +1,403 @@
+//! Résolution exacte d'un MDP actualisé par programmation linéaire.
+//!
+//! LP primal, sur les états non terminaux S' :
+//!
+//! ```text
+//! min  Σ_s μ(s) V(s)
+//! s.c. V(s) - γ Σ_s' P(s' | s, a) V(s') ≥ r(s, a)      pour tout s ∈ S', a
+//! ```
+//!
+//! et son dual, dont les variables sont les mesures d'occupation actualisées des couples
+//! état-action (μ uniforme sur S') :
+//!
+//! ```text
+//! max  Σ_{s,a} r(s, a) x(s, a)
+//! s.c. Σ_a x(s', a) - γ Σ_{s,a} P(s' | s, a) x(s, a) = μ(s')      pour tout s' ∈ S'
+//!      x ≥ 0
+//! ```
+//!
+//! Le dual est déjà en forme standard (égalités, second membre positif) : le simplexe tourne
+//! sur lui, et V* se lit dans ses multiplicateurs, si bien qu'une seule résolution donne les
+//! deux solutions.
+
+use crate::algorithms::dp::sweeps::terminal_mask;
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use std::fmt::{Display, Formatter};
+
+/// Tolérance numérique des tests de signe du simplexe
+const EPS: f64 = 1e-9;
+/// Pivots dégénérés consécutifs avant de passer à la règle de Bland (anti-cyclage)
+const DEGENERATE_STREAK: usize = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpError {
+    /// Le LP n'est borné que pour 0 ≤ γ < 1
+    DiscountOutOfRange(f64),
+    /// Plus de pivots que la limite fixée
+    PivotLimit(usize),
+    /// Phase 1 sans solution réalisable : probabilités de transition incohérentes
+    Infeasible,
+    /// Objectif non borné, ce qui ne doit pas arriver avec γ < 1
+    Unbounded,
+}
+
+impl Display for LpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LpError::DiscountOutOfRange(gamma) => {
+                write!(f, "gamma = {} hors de [0, 1) : LP non borné", gamma)
+            }
+            LpError::PivotLimit(pivots) => write!(f, "Limite de {} pivots atteinte", pivots),
+            LpError::Infeasible => write!(f, "LP sans solution réalisable"),
+            LpError::Unbounded => write!(f, "LP non borné"),
+        }
+    }
+}
+
+impl std::error::Error for LpError {}
+
+/// Solution du LP : solution primale (V*), duale (occupations) et policy optimale
+#[derive(Debug)]
+pub struct LpSolution {
+    pub policy: DeterministicPolicy,
+    pub values: Vec<f64>,
+    /// occupancy[s * num_actions + a] = x(s, a), nulle pour les états terminaux
+    pub occupancy: Vec<f64>,
+    /// Valeur optimale Σ_s μ(s) V*(s) = Σ_{s,a} r(s, a) x(s, a)
+    pub objective: f64,
+    /// Distribution initiale μ (uniforme sur les états non terminaux)
+    pub initial_distribution: Vec<f64>,
+    pub pivots: usize,
+    num_actions: usize,
+}
+
+impl LpSolution {
+    /// Occupation actualisée de (s, a) : E[Σ_t γ^t 1{S_t = s, A_t = a}] en partant de μ
+    pub fn occupancy(&self, s: usize, a: usize) -> f64 {
+        self.occupancy[s * self.num_actions + a]
+    }
+
+    /// Occupation actualisée de l'état s, toutes actions confondues
+    pub fn state_occupancy(&self, s: usize) -> f64 {
+        self.occupancy[s * self.num_actions..(s + 1) * self.num_actions].iter().sum()
+    }
+}
+
+/// Tableau du simplexe pour min cᵀx, Ax = b, x ≥ 0, avec une variable artificielle par ligne
+struct Simplex {
+    rows: usize,
+    /// Colonnes structurelles, suivies des `rows` colonnes artificielles
+    structural: usize,
+    width: usize,
+    /// tableau[i * (width + 1) + j], la dernière colonne est le second membre
+    tableau: Vec<f64>,
+    /// Coûts réduits ; reduced[width] = -(valeur de l'objectif)
+    reduced: Vec<f64>,
+    basis: Vec<usize>,
+    pivots: usize,
+    max_pivots: usize,
+}
+
+impl Simplex {
+    /// `columns[j]` = entrées non nulles (ligne, coefficient) de la colonne j ; b ≥ 0
+    fn new(rows: usize, columns: &[Vec<(usize, f64)>], b: &[f64], max_pivots: usize) -> Self {
+        let structural = columns.len();
+        let width = structural + rows;
+        let mut tableau = vec![0.0; rows * (width + 1)];
+        for (j, column) in columns.iter().enumerate() {
+            for &(i, value) in column {
+                tableau[i * (width + 1) + j] += value;
+            }
+        }
+        for i in 0..rows {
+            tableau[i * (width + 1) + structural + i] = 1.0;
+            tableau[i * (width + 1) + width] = b[i];
+        }
+        Simplex {
+            rows,
+            structural,
+            width,
+            tableau,
+            reduced: vec![0.0; width + 1],
+            basis: (structural..width).collect(),
+            pivots: 0,
+            max_pivots,
+        }
+    }
+
+    fn at(&self, i: usize, j: usize) -> f64 {
+        self.tableau[i * (self.width + 1) + j]
+    }
+
+    fn rhs(&self, i: usize) -> f64 {
+        self.at(i, self.width)
+    }
+
+    /// Recalcule les coûts réduits d - c_Bᵀ B⁻¹A pour les coûts `cost`
+    fn set_cost(&mut self, cost: &[f64]) {
+        self.reduced[..self.width].copy_from_slice(cost);
+        self.reduced[self.width] = 0.0;
+        for i in 0..self.rows {
+            let c_b = cost[self.basis[i]];
+            if c_b != 0.0 {
+                for j in 0..=self.width {
+                    self.reduced[j] -= c_b * self.at(i, j);
+                }
+            }
+        }
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let stride = self.width + 1;
+        let pivot = self.at(row, col);
+        for j in 0..stride {
+            self.tableau[row * stride + j] /= pivot;
+        }
+        let pivot_row = self.tableau[row * stride..(row + 1) * stride].to_vec();
+        for i in (0..self.rows).filter(|&i| i != row) {
+            let factor = self.at(i, col);
+            if factor != 0.0 {
+                for (j, &p) in pivot_row.iter().enumerate() {
+                    self.tableau[i * stride + j] -= factor * p;
+                }
+            }
+        }
+        let factor = self.reduced[col];
+        for (j, &p) in pivot_row.iter().enumerate() {
+            self.reduced[j] -= factor * p;
+        }
+        self.basis[row] = col;
+        self.pivots += 1;
+    }
+
+    /// Pivote jusqu'à l'optimum ; seules les `entering` premières colonnes peuvent entrer
+    fn optimize(&mut self, entering: usize) -> Result<(), LpError> {
+        let mut degenerate = 0;
+        loop {
+            // Dantzig (coût réduit le plus négatif), Bland après une série de pivots dégénérés
+            let candidates = (0..entering).filter(|&j| self.reduced[j] < -EPS);
+            let col = if degenerate < DEGENERATE_STREAK {
+                candidates.min_by(|&a, &b| self.reduced[a].total_cmp(&self.reduced[b]))
+            } else {
+                candidates.min()
+            };
+            let Some(col) = col else {
+                return Ok(());
+            };
+
+            // Test du ratio, plus petit indice de base en cas d'égalité
+            let mut leaving: Option<(usize, f64)> = None;
+            for i in (0..self.rows).filter(|&i| self.at(i, col) > EPS) {
+                let ratio = self.rhs(i) / self.at(i, col);
+                let better = match leaving {
+                    None => true,
+                    Some((l, best)) => {
+                        ratio < best - EPS || (ratio <= best + EPS && self.basis[i] < self.basis[l])
+                    }
+                };
+                if better {
+                    leaving = Some((i, ratio));
+                }
+            }
+            let Some((row, ratio)) = leaving else {
+                return Err(LpError::Unbounded);
+            };
+
+            if self.pivots >= self.max_pivots {
+                return Err(LpError::PivotLimit(self.max_pivots));
+            }
+            degenerate = if ratio <= EPS { degenerate + 1 } else { 0 };
+            self.pivot(row, col);
+        }
+    }
+
+    /// Deux phases : la phase 1 minimise la somme des artificielles, la phase 2 minimise `cost`
+    fn solve(&mut self, cost: &[f64]) -> Result<(), LpError> {
+        let mut phase_one = vec![0.0; self.width];
+        phase_one[self.structural..].fill(1.0);
+        self.set_cost(&phase_one);
+        self.optimize(self.width)?;
+        if -self.reduced[self.width] > EPS.sqrt() {
+            return Err(LpError::Infeasible);
+        }
+
+        // Artificielles restées dans la base (à 0) : on les remplace par une colonne structurelle
+        for i in 0..self.rows {
+            if self.basis[i] >= self.structural {
+                if let Some(col) = (0..self.structural).find(|&j| self.at(i, j).abs() > EPS) {
+                    self.pivot(i, col);
+                }
+            }
+        }
+
+        let mut phase_two = cost.to_vec();
+        phase_two.resize(self.width, 0.0);
+        self.set_cost(&phase_two);
+        self.optimize(self.structural)
+    }
+
+    /// Valeur des colonnes structurelles dans la solution de base courante
+    fn primal(&self) -> Vec<f64> {
+        let mut x = vec![0.0; self.structural];
+        for (i, &j) in self.basis.iter().enumerate() {
+            if j < self.structural {
+                x[j] = self.rhs(i);
+            }
+        }
+        x
+    }
+
+    /// Multiplicateurs y = c_Bᵀ B⁻¹, lus sous les colonnes artificielles (de coût nul en phase 2)
+    fn multipliers(&self) -> Vec<f64> {
+        (0..self.rows).map(|i| -self.reduced[self.structural + i]).collect()
+    }
+}
+
+/// Résout le MDP par programmation linéaire (simplexe en deux phases sur la forme duale).
+///
+/// Les valeurs sont nulles dans les états terminaux. La policy choisit dans chaque état l'action
+/// occupée par la solution de base (μ > 0 partout, donc une action par état) : par les écarts
+/// complémentaires elle est gloutonne par rapport à V*, mais peut départager des actions
+/// équivalentes autrement que `value_iteration`. Le tableau est dense : à réserver aux
+/// modèles de quelques centaines d'états.
+pub fn linear_programming(
+    env: &dyn DynamicProgramingEnvironment,
+    gamma: f64,
+) -> Result<LpSolution, LpError> {
+    if !(0.0..1.0).contains(&gamma) {
+        return Err(LpError::DiscountOutOfRange(gamma));
+    }
+    let num_actions = env.num_actions();
+    let is_terminal = terminal_mask(env);
+    let states: Vec<usize> = (0..env.num_states()).filter(|&s| !is_terminal[s]).collect();
+    let mut row_of = vec![None; env.num_states()];
+    for (i, &s) in states.iter().enumerate() {
+        row_of[s] = Some(i);
+    }
+
+    // Colonne (i, a) : coefficients de x(states[i], a) dans les contraintes de flot
+    let mut columns = Vec::with_capacity(states.len() * num_actions);
+    let mut rewards = Vec::with_capacity(states.len() * num_actions);
+    for (i, &s) in states.iter().enumerate() {
+        for a in 0..num_actions {
+            let mut column = vec![(i, 1.0)];
+            let mut r = 0.0;
+            for (s_prime, r_index, p) in env.transitions_from(s, a) {
+                r += p * env.get_reward(r_index);
+                if let Some(k) = row_of[s_prime] {
+                    column.push((k, -gamma * p));
+                }
+            }
+            columns.push(column);
+            rewards.push(r);
+        }
+    }
+
+    let mu = if states.is_empty() { 0.0 } else { 1.0 / states.len() as f64 };
+    let b = vec![mu; states.len()];
+    let cost: Vec<f64> = rewards.iter().map(|r| -r).collect();
+    let max_pivots = 50 * (states.len() + columns.len()).max(1);
+
+    let mut simplex = Simplex::new(states.len(), &columns, &b, max_pivots);
+    simplex.solve(&cost)?;
+
+    let x = simplex.primal();
+    let y = simplex.multipliers();
+    let mut values = vec![0.0; env.num_states()];
+    let mut occupancy = vec![0.0; env.num_states() * num_actions];
+    let mut initial_distribution = vec![0.0; env.num_states()];
+    let mut table = vec![0; env.num_states()];
+    for (i, &s) in states.iter().enumerate() {
+        // min -rᵀx : les multiplicateurs valent -V*
+        values[s] = -y[i];
+        initial_distribution[s] = mu;
+        occupancy[s * num_actions..(s + 1) * num_actions]
+            .copy_from_slice(&x[i * num_actions..(i + 1) * num_actions]);
+        table[s] = (0..num_actions)
+            .max_by(|&a, &b| x[i * num_actions + a].total_cmp(&x[i * num_actions + b]))
+            .unwrap_or(0);
+    }
+
+    Ok(LpSolution {
+        policy: DeterministicPolicy::from_vec(env, table),
+        objective: rewards.iter().zip(&x).map(|(r, x)| r * x).sum(),
+        values,
+        occupancy,
+        initial_distribution,
+        pivots: simplex.pivots,
+        num_actions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::sweeps::{q_value, value_iteration_sweeps, SweepMode};
+    use crate::core::envs::Environment;
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+    use crate::environments::line_world::line_world_dp;
+    use crate::environments::pierre_feuille_ciseaux::pierre_feuille_ciseaux_dp;
+
+    fn assert_matches_value_iteration(env: &dyn DynamicProgramingEnvironment, gamma: f64) {
+        let (_, vi_values, _) =
+            value_iteration_sweeps(env, 1e-12, gamma, 100_000, SweepMode::GaussSeidel);
+        let lp = linear_programming(env, gamma).unwrap();
+        let terminals = env.get_terminal_states();
+        for (s, (v, w)) in lp.values.iter().zip(&vi_values).enumerate() {
+            assert!((v - w).abs() < 1e-6, "état {} : LP {} != VI {}", s, v, w);
+            // Les égalités peuvent être départagées autrement : on vérifie l'optimalité
+            if !terminals.contains(&s) {
+                let q = q_value(env, s, lp.policy.policy_table[s], &vi_values, gamma);
+                assert!((q - w).abs() < 1e-6, "état {} : action sous-optimale", s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lp_matches_value_iteration() {
+        assert_matches_value_iteration(&line_world_dp(), 0.9);
+        assert_matches_value_iteration(&grid_world(), 0.9);
+        assert_matches_value_iteration(&pierre_feuille_ciseaux_dp(), 0.95);
+    }
+
+    #[test]
+    fn test_occupancies_satisfy_flow_and_duality() {
+        let env = grid_world();
+        let gamma = 0.9;
+        let lp = linear_programming(&env, gamma).unwrap();
+        let terminals = env.get_terminal_states();
+
+        // Flot : Σ_a x(s', a) = μ(s') + γ Σ_{s,a} P(s' | s, a) x(s, a)
+        let mut inflow = lp.initial_distribution.clone();
+        for s in 0..env.num_states() {
+            for a in 0..env.num_actions() {
+                for (s_prime, _, p) in env.transitions_from(s, a) {
+                    inflow[s_prime] += gamma * p * lp.occupancy(s, a);
+                }
+            }
+        }
+        for s in (0..env.num_states()).filter(|s| !terminals.contains(s)) {
+            assert!((lp.state_occupancy(s) - inflow[s]).abs() < 1e-9);
+        }
+        assert!(lp.occupancy.iter().all(|&x| x >= 0.0));
+
+        // Dualité forte : μᵀV* = rᵀx*
+        let primal: f64 = lp.initial_distribution.iter().zip(&lp.values).map(|(m, v)| m * v).sum();
+        assert!((primal - lp.objective).abs() < 1e-9);
+
+        // La policy optimale est la seule à occuper les états non terminaux
+        for s in (0..env.num_states()).filter(|s| !terminals.contains(s)) {
+            let a = lp.policy.policy_table[s];
+            assert!((lp.occupancy(s, a) - lp.state_occupancy(s)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_rejects_undiscounted_problem() {
+        assert_eq!(
+            linear_programming(&line_world_dp(), 1.0).unwrap_err(),
+            LpError::DiscountOutOfRange(1.0)
+        );
+    }
+}
@@ -1,2 +1,13 @@
+pub mod average_reward;
+pub mod compare;
+pub mod exact_evaluation;
+pub mod finite_horizon;
+pub mod linalg;
+pub mod linear_programming;
+pub mod parallel;
 pub mod policy_iteration;
+pub mod prioritized;
+pub mod q_value_iteration;
+pub mod report;
+pub mod sweeps;
 pub mod value_iteration;
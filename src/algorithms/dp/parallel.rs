@@ -0,0 +1,173 @@
+//! Balayages Jacobi répartis sur plusieurs threads : les états sont découpés en blocs
+//! contigus, chaque thread écrit son bloc du nouveau vecteur de valeurs en ne lisant que
+//! l'ancien. Les résultats sont identiques, au bit près, à ceux des balayages Jacobi
+//! séquentiels de `sweeps`.
+
+use crate::algorithms::dp::report::SolveReport;
+use crate::algorithms::dp::sweeps::{greedy_backup, greedy_policy, q_value, terminal_mask, SweepStats};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use std::thread;
+use std::time::Instant;
+
+/// Nombre de threads effectif : `threads`, ou le nombre de cœurs disponibles si 0
+pub fn resolve_threads(threads: usize) -> usize {
+    if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    }
+}
+
+/// Un balayage Jacobi : `next[s] = backup(s, values)` pour les états non terminaux,
+/// calculé par `threads` threads. Renvoie l'écart max.
+fn parallel_sweep(
+    values: &[f64],
+    next: &mut [f64],
+    is_terminal: &[bool],
+    threads: usize,
+    stats: &mut SweepStats,
+    backup: &(dyn Fn(usize, &[f64]) -> f64 + Sync),
+) -> f64 {
+    let chunk = values.len().div_ceil(threads).max(1);
+    let (delta, backups) = thread::scope(|scope| {
+        let handles: Vec<_> = next
+            .chunks_mut(chunk)
+            .enumerate()
+            .map(|(i, block)| {
+                scope.spawn(move || {
+                    let mut delta: f64 = 0.0;
+                    let mut backups = 0;
+                    for (k, v) in block.iter_mut().enumerate() {
+                        let s = i * chunk + k;
+                        if is_terminal[s] {
+                            *v = values[s];
+                            continue;
+                        }
+                        *v = backup(s, values);
+                        delta = delta.max((*v - values[s]).abs());
+                        backups += 1;
+                    }
+                    (delta, backups)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Un thread de balayage a paniqué"))
+            .fold((0.0f64, 0), |(d, b), (delta, backups)| (d.max(delta), b + backups))
+    });
+    stats.backups += backups;
+    stats.sweeps += 1;
+    stats.residual = delta;
+    delta
+}
+
+/// Value iteration à balayages Jacobi parallèles
+pub fn parallel_value_iteration(
+    env: &(dyn DynamicProgramingEnvironment + Sync),
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    threads: usize,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats) {
+    let (policy, values, stats, _) =
+        parallel_value_iteration_with_report(env, theta, gamma, max_iter, threads);
+    (policy, values, stats)
+}
+
+/// `parallel_value_iteration`, avec en plus le rapport de convergence
+pub fn parallel_value_iteration_with_report(
+    env: &(dyn DynamicProgramingEnvironment + Sync),
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    threads: usize,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats, SolveReport) {
+    let start = Instant::now();
+    let threads = resolve_threads(threads);
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut next = values.clone();
+    let mut stats = SweepStats::default();
+    let mut report = SolveReport::default();
+
+    for _ in 0..max_iter {
+        let delta = parallel_sweep(&values, &mut next, &is_terminal, threads, &mut stats, &|s, v| {
+            greedy_backup(env, s, v, gamma).1
+        });
+        std::mem::swap(&mut values, &mut next);
+        report.iterations += 1;
+        report.delta_history.push(delta);
+        if delta < theta {
+            report.converged = true;
+            break;
+        }
+    }
+
+    report.finish(env, &values, gamma, start.elapsed());
+    (greedy_policy(env, &values, &is_terminal, gamma), values, stats, report)
+}
+
+/// Évaluation de `policy` à balayages Jacobi parallèles
+pub fn parallel_policy_evaluation(
+    env: &(dyn DynamicProgramingEnvironment + Sync),
+    policy: &DeterministicPolicy,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    threads: usize,
+) -> (Vec<f64>, SweepStats) {
+    let threads = resolve_threads(threads);
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut next = values.clone();
+    let mut stats = SweepStats::default();
+
+    for _ in 0..max_iter {
+        let delta = parallel_sweep(&values, &mut next, &is_terminal, threads, &mut stats, &|s, v| {
+            q_value(env, s, policy.policy_table[s], v, gamma)
+        });
+        std::mem::swap(&mut values, &mut next);
+        if delta < theta {
+            break;
+        }
+    }
+    (values, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::sweeps::{policy_evaluation_sweeps, value_iteration_sweeps, SweepMode};
+    use crate::environments::grid_world::dynamic_programming::grid_world_sized;
+
+    #[test]
+    fn test_parallel_value_iteration_is_identical_to_sequential_jacobi() {
+        let env = grid_world_sized(30, 40);
+        let (policy, values, stats) =
+            value_iteration_sweeps(&env, 1e-8, 0.99, 10_000, SweepMode::Jacobi);
+        for threads in [1, 3, 8] {
+            let (p, v, st) = parallel_value_iteration(&env, 1e-8, 0.99, 10_000, threads);
+            assert_eq!(v, values);
+            assert_eq!(p.policy_table, policy.policy_table);
+            assert_eq!(st, stats);
+        }
+    }
+
+    #[test]
+    fn test_parallel_policy_evaluation_is_identical_to_sequential_jacobi() {
+        let env = grid_world_sized(7, 9);
+        // Droite, puis bas une fois dans la dernière colonne
+        let table = (0..63).map(|s| if s % 9 == 8 { 1 } else { 3 }).collect();
+        let policy = DeterministicPolicy::from_vec(&env, table);
+        let (values, stats) =
+            policy_evaluation_sweeps(&env, &policy, 1e-10, 0.95, 10_000, SweepMode::Jacobi);
+        // Plus de threads que d'états par bloc, et le nombre de cœurs disponibles
+        for threads in [64, 100, 0] {
+            let (v, st) = parallel_policy_evaluation(&env, &policy, 1e-10, 0.95, 10_000, threads);
+            assert_eq!(v, values);
+            assert_eq!(st, stats);
+        }
+    }
+}
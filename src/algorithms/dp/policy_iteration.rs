@@ -1,5 +1,7 @@
+use crate::algorithms::dp::report::SolveReport;
 use crate::core::envs::DynamicProgramingEnvironment;
 use crate::core::policies::DeterministicPolicy;
+use std::time::Instant;
 
 pub fn policy_evaluation(
     env: &dyn DynamicProgramingEnvironment,
@@ -7,30 +9,42 @@ pub fn policy_evaluation(
     theta: f64,
     gamma: f64,
 ) -> Vec<f64> {
-    let mut values = vec![0.0; env.num_states()];
+    evaluate(env, policy, theta, gamma, &mut Vec::new())
+}
 
-    for ts in &env.get_terminal_states() {
-        values[*ts] = 0.0;
+/// Évaluation itérative ; l'écart max de chaque balayage est ajouté à `delta_history`
+fn evaluate(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &DeterministicPolicy,
+    theta: f64,
+    gamma: f64,
+    delta_history: &mut Vec<f64>,
+) -> Vec<f64> {
+    let mut values = vec![0.0; env.num_states()];
+    let mut is_terminal = vec![false; env.num_states()];
+    for ts in env.get_terminal_states() {
+        is_terminal[ts] = true;
     }
 
     loop {
         let mut delta: f64 = 0.0;
         for s in 0..env.num_states() {
+            if is_terminal[s] {
+                continue;
+            }
             let v_old: f64 = values[s];
+            let a = policy.get_action(&s);
             let mut total = 0.0;
 
-            for s_prime in 0..env.num_states() {
-                for r_index in 0..env.num_rewards() {
-                    let r = env.get_reward(r_index);
-                    let a = policy.get_action(&s);
-                    let p = env.get_transition_prob(s, a, s_prime, r_index);
-                    total += p * (r + gamma * values[s_prime]);
-                }
+            for (s_prime, r_index, p) in env.transitions_from(s, a) {
+                let r = env.get_reward(r_index);
+                total += p * (r + gamma * values[s_prime]);
             }
 
             values[s] = total;
             delta = delta.max((v_old - total).abs());
         }
+        delta_history.push(delta);
 
         if delta < theta {
             break;
@@ -46,7 +60,7 @@ pub fn policy_improvement(
     v: &Vec<f64>,
     gamma: f64,
 ) -> (DeterministicPolicy, bool) {
-    let mut new_policy = DeterministicPolicy::new_det_pol(env);
+    let mut new_policy = DeterministicPolicy::from_vec(env, vec![0; env.num_states()]);
     let mut policy_is_stable = true;
 
     for s in 0..env.num_states() {
@@ -57,66 +71,75 @@ pub fn policy_improvement(
         for a in 0..env.num_actions() {
             let mut score = 0.0;
 
-            for s_prime in 0..env.num_states() {
-                for r_index in 0..env.num_rewards() {
-                    let r = env.get_reward(r_index);
-                    let p = env.get_transition_prob(s, a, s_prime, r_index);
-                    score += p * (r + gamma * v[s_prime]);
-                }
-
-                if best_action.is_none() || score > best_value {
-                    best_action = Some(a);
-                    best_value = score;
-                }
+            for (s_prime, r_index, p) in env.transitions_from(s, a) {
+                let r = env.get_reward(r_index);
+                score += p * (r + gamma * v[s_prime]);
             }
 
-            let choosen_action = best_action.unwrap();
-            if choosen_action != old_actions {
-                policy_is_stable = false;
+            if best_action.is_none() || score > best_value {
+                best_action = Some(a);
+                best_value = score;
             }
-            new_policy.set_action(&s, choosen_action);
         }
+
+        let choosen_action = best_action.unwrap();
+        if choosen_action != old_actions {
+            policy_is_stable = false;
+        }
+        new_policy.set_action(&s, choosen_action);
     }
     (new_policy, policy_is_stable)
 }
 
+/// Policy iteration ; le rapport indique si la policy s'est stabilisée avant `max_iter`
+/// et combien d'actions chaque amélioration a changées
 pub fn policy_iteration(
     env: &dyn DynamicProgramingEnvironment,
     theta: f64,
     gamma: f64,
     max_iter: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    let mut policy = DeterministicPolicy::new_det_pol(env);
+) -> (DeterministicPolicy, Vec<f64>, SolveReport) {
+    let start = Instant::now();
+    let mut report = SolveReport::default();
+    let mut policy = DeterministicPolicy::from_vec(env, vec![0; env.num_states()]);
     let mut values = vec![0.0; env.num_states()];
 
-    for ep in 0..max_iter {
-        if ep % 100 == 0 {
-            println!("=== Iteration {} ===", ep);
-        }
-        
-        let v = policy_evaluation(env, &policy, theta, gamma);
+    for _ in 0..max_iter {
+        let v = evaluate(env, &policy, theta, gamma, &mut report.delta_history);
 
         let (new_policy, policy_is_stable) = policy_improvement(env, &policy, &v, gamma);
+        report.iterations += 1;
+        report.policy_changes.push(
+            new_policy
+                .policy_table
+                .iter()
+                .zip(&policy.policy_table)
+                .filter(|(a, b)| a != b)
+                .count(),
+        );
 
-        if policy_is_stable {
-            return (new_policy, v);
-        }
         policy = new_policy;
         values = v;
+        if policy_is_stable {
+            report.converged = true;
+            break;
+        }
     }
 
-    (policy, values)
+    report.finish(env, &values, gamma, start.elapsed());
+    (policy, values, report)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::envs::DPEnvironment;
     use crate::environments::line_world::line_world_dp;
 
     #[test]
     fn test_policy_evaluation() {
         let env = line_world_dp();
-        let mut policy: DeterministicPolicy = DeterministicPolicy::new_det_pol(&env);
+        let mut policy: DeterministicPolicy = DeterministicPolicy::from_vec(&env, vec![0; env.states.len()]);
         for s in &env.states {
             policy.set_action(s, 1);
         }
@@ -140,7 +163,7 @@ mod tests {
         let env = line_world_dp();
         let expected = vec![0, 1, 1, 1, 0];
         for _ in 0..50{
-            let (policy, _) = policy_iteration(&env, 0.0001, 0.99, 1000);
+            let (policy, _, _) = policy_iteration(&env, 0.0001, 0.99, 1000);
 
 
             for s in 0..3 {
@@ -154,4 +177,33 @@ mod tests {
         }
 
     }
+
+    /// Régression : l'action est choisie sur la valeur complète de chaque action, pas sur
+    /// une somme partielle des transitions (la première transition de l'action 0 rapporte
+    /// +1, mais son espérance est -4.5 contre 0 pour l'action 1)
+    #[test]
+    fn test_improvement_compares_full_expected_values() {
+        let mut env = DPEnvironment::new(3, 2, 3, vec![-10.0, 0.0, 1.0], vec![1, 2]);
+        env.set_transition_prob(0, 0, 1, 2, 0.5);
+        env.set_transition_prob(0, 0, 2, 0, 0.5);
+        env.set_transition_prob(0, 1, 1, 1, 1.0);
+
+        let old_policy = DeterministicPolicy::from_vec(&env, vec![0; 3]);
+        let (policy, stable) = policy_improvement(&env, &old_policy, &vec![0.0; 3], 0.9);
+        assert_eq!(policy.get_action(&0), 1);
+        assert!(!stable);
+    }
+
+    /// Régression : la stabilité n'est jugée que sur l'action finale de chaque état, et
+    /// une policy déjà optimale n'est pas modifiée
+    #[test]
+    fn test_improvement_of_optimal_policy_is_stable() {
+        let env = line_world_dp();
+        // Les états terminaux n'ont pas de transitions : la première action y est gardée
+        let optimal = DeterministicPolicy::from_vec(&env, vec![0, 1, 1, 1, 0]);
+        let values = policy_evaluation(&env, &optimal, 1e-10, 0.9);
+        let (policy, stable) = policy_improvement(&env, &optimal, &values, 0.9);
+        assert!(stable);
+        assert_eq!(policy.policy_table, optimal.policy_table);
+    }
 }
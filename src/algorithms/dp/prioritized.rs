@@ -0,0 +1,165 @@
+//! Value iteration priorisée (prioritized sweeping) : les états sont mis à jour un par un,
+//! par borne de résidu de Bellman décroissante, au lieu de balayer tout le modèle.
+
+use crate::algorithms::dp::sweeps::{greedy_backup, greedy_policy, terminal_mask, SweepStats};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Entrée de la file : (borne du résidu, état)
+#[derive(Debug, PartialEq)]
+struct Priority(f64, usize);
+
+impl Eq for Priority {}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// predecessors[s'] = [(s, max_a P(s' | s, a))] pour les états s non terminaux
+pub fn predecessors(env: &dyn DynamicProgramingEnvironment) -> Vec<Vec<(usize, f64)>> {
+    let is_terminal = terminal_mask(env);
+    let mut predecessors = vec![Vec::new(); env.num_states()];
+    for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+        let mut weights: HashMap<usize, f64> = HashMap::new();
+        for a in 0..env.num_actions() {
+            // P(s' | s, a) somme les indices de reward
+            let mut to: HashMap<usize, f64> = HashMap::new();
+            for (s_prime, _, p) in env.transitions_from(s, a) {
+                *to.entry(s_prime).or_insert(0.0) += p;
+            }
+            for (s_prime, p) in to {
+                let w = weights.entry(s_prime).or_insert(0.0);
+                *w = w.max(p);
+            }
+        }
+        for (s_prime, w) in weights {
+            predecessors[s_prime].push((s, w));
+        }
+    }
+    for preds in &mut predecessors {
+        preds.sort_unstable_by_key(|&(s, _)| s);
+    }
+    predecessors
+}
+
+/// Value iteration priorisée.
+///
+/// Chaque état porte une borne supérieure de son résidu |max_a Q(s, a) - V(s)|, initialisée
+/// au résidu exact. Le backup de s remet sa borne à 0 et augmente celle de chaque
+/// prédécesseur p de γ · max_a P(s | p, a) · |ΔV(s)|. L'état de plus grande borne est traité
+/// en premier ; quand toutes les bornes sont sous `theta`, le résidu de Bellman l'est aussi.
+///
+/// `stats.backups` compte les évaluations de max_a Q(s, a), y compris le calcul initial des
+/// résidus ; `stats.sweeps` reste à 0.
+pub fn prioritized_value_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_backups: usize,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats) {
+    let is_terminal = terminal_mask(env);
+    let predecessors = predecessors(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut bound = vec![0.0; env.num_states()];
+    let mut queue = BinaryHeap::new();
+    let mut stats = SweepStats::default();
+
+    for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+        bound[s] = (greedy_backup(env, s, &values, gamma).1 - values[s]).abs();
+        stats.backups += 1;
+        if bound[s] >= theta {
+            queue.push(Priority(bound[s], s));
+        }
+    }
+
+    while let Some(Priority(priority, s)) = queue.pop() {
+        // Entrée périmée : la borne a changé depuis
+        if priority != bound[s] {
+            continue;
+        }
+        if stats.backups >= max_backups {
+            break;
+        }
+
+        let v = greedy_backup(env, s, &values, gamma).1;
+        let change = (v - values[s]).abs();
+        values[s] = v;
+        bound[s] = 0.0;
+        stats.backups += 1;
+
+        for &(p, w) in &predecessors[s] {
+            let increase = gamma * w * change;
+            if increase == 0.0 {
+                continue;
+            }
+            bound[p] += increase;
+            if bound[p] >= theta {
+                queue.push(Priority(bound[p], p));
+            }
+        }
+    }
+
+    stats.residual = bound.iter().copied().fold(0.0, f64::max);
+    (greedy_policy(env, &values, &is_terminal, gamma), values, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::sweeps::{value_iteration_sweeps, SweepMode};
+    use crate::environments::grid_world::dynamic_programming::{grid_world, grid_world_sized};
+    use crate::environments::line_world::line_world_dp;
+
+    #[test]
+    fn test_predecessors_of_line_world() {
+        let preds = predecessors(&line_world_dp());
+        // 2 est atteint depuis 1 (droite) et 3 (gauche) ; les terminaux n'ont pas de successeurs
+        assert_eq!(preds[2], vec![(1, 1.0), (3, 1.0)]);
+        assert_eq!(preds[0], vec![(1, 1.0)]);
+        assert!(preds.iter().flatten().all(|&(p, _)| p != 0 && p != 4));
+    }
+
+    #[test]
+    fn test_same_solution_as_value_iteration() {
+        let env = grid_world();
+        let (vi_policy, vi_values, _) =
+            value_iteration_sweeps(&env, 1e-10, 0.9, 10_000, SweepMode::GaussSeidel);
+        let (policy, values, stats) = prioritized_value_iteration(&env, 1e-10, 0.9, usize::MAX);
+
+        assert!(stats.residual < 1e-10);
+        assert_eq!(policy.policy_table, vi_policy.policy_table);
+        for (v, w) in values.iter().zip(&vi_values) {
+            assert!((v - w).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_far_fewer_backups_on_large_sparse_grid() {
+        let env = grid_world_sized(40, 40);
+        let theta = 1e-6;
+        let (_, vi_values, vi) =
+            value_iteration_sweeps(&env, theta, 0.99, 100_000, SweepMode::GaussSeidel);
+        let (_, values, ps) = prioritized_value_iteration(&env, theta, 0.99, usize::MAX);
+
+        assert!(ps.residual < theta);
+        for (v, w) in values.iter().zip(&vi_values) {
+            assert!((v - w).abs() < 1e-3);
+        }
+        assert!(
+            ps.backups * 4 < vi.backups,
+            "priorisée : {} backups, VI : {}",
+            ps.backups,
+            vi.backups
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Value iteration sur les valeurs d'action : garde Q*(s, a) au lieu de ne renvoyer que V*,
+//! avec l'écart d'action et l'ensemble des actions optimales de chaque état.
+
+use crate::algorithms::dp::sweeps::{q_value, terminal_mask, SweepStats};
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use crate::core::q_table::QTable;
+
+/// Résultat de `q_value_iteration`
+#[derive(Debug)]
+pub struct QValueIteration {
+    /// Q*(s, a), au format partagé avec les algorithmes MC, TD et de planning
+    pub q: QTable,
+    /// V*(s) = max_a Q*(s, a)
+    pub values: Vec<f64>,
+    /// max_a Q*(s, a) - max des Q*(s, a) des actions non optimales (0 si toutes sont optimales,
+    /// ainsi que dans les états terminaux)
+    pub action_gaps: Vec<f64>,
+    /// Actions à moins de `tie_tolerance` du max, par ordre croissant ; vide pour les terminaux
+    pub optimal_actions: Vec<Vec<usize>>,
+    /// Première action optimale de chaque état (action 0 dans les états terminaux)
+    pub policy: DeterministicPolicy,
+    pub stats: SweepStats,
+    is_terminal: Vec<bool>,
+}
+
+impl QValueIteration {
+    /// Écart quadratique moyen entre `q` et Q* sur les couples (s, a) non terminaux
+    pub fn rmse(&self, q: &QTable) -> f64 {
+        let num_actions = self.q.num_actions();
+        let mut sum = 0.0;
+        let mut count = 0;
+        for s in (0..self.values.len()).filter(|&s| !self.is_terminal[s]) {
+            for a in 0..num_actions {
+                sum += (q.get(s, a) - self.q.get(s, a)).powi(2);
+                count += 1;
+            }
+        }
+        if count == 0 {
+            0.0
+        } else {
+            (sum / count as f64).sqrt()
+        }
+    }
+
+    /// Vrai si `a` est optimale dans `s`
+    pub fn is_optimal(&self, s: usize, a: usize) -> bool {
+        self.optimal_actions[s].contains(&a)
+    }
+}
+
+/// Q-value iteration en place : Q(s, a) <- Σ P(s', r | s, a) [r + γ max_a' Q(s', a')],
+/// jusqu'à ce que la plus grande variation d'un balayage passe sous `theta`.
+///
+/// Les actions dont la valeur est à moins de `tie_tolerance` du max sont toutes considérées
+/// optimales, au lieu de départager les égalités arbitrairement.
+pub fn q_value_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    tie_tolerance: f64,
+) -> QValueIteration {
+    let num_actions = env.num_actions();
+    let is_terminal = terminal_mask(env);
+    let mut q = QTable::dense(env.num_states(), num_actions);
+    // values[s] = max_a Q(s, a), tenu à jour pendant le balayage (Gauss-Seidel)
+    let mut values = vec![0.0; env.num_states()];
+    let mut stats = SweepStats::default();
+
+    for _ in 0..max_iter {
+        let mut delta: f64 = 0.0;
+        for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+            let mut best = f64::NEG_INFINITY;
+            for a in 0..num_actions {
+                let q_s_a = q_value(env, s, a, &values, gamma);
+                delta = delta.max((q_s_a - q.get(s, a)).abs());
+                q.set(s, a, q_s_a);
+                best = best.max(q_s_a);
+            }
+            values[s] = best;
+            stats.backups += 1;
+        }
+        stats.sweeps += 1;
+        stats.residual = delta;
+        if delta < theta {
+            break;
+        }
+    }
+
+    let mut action_gaps = vec![0.0; env.num_states()];
+    let mut optimal_actions = vec![Vec::new(); env.num_states()];
+    let mut table = vec![0; env.num_states()];
+    for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+        let row = q.row(s);
+        let best = values[s];
+        let (optimal, others): (Vec<usize>, Vec<usize>) =
+            (0..num_actions).partition(|&a| best - row[a] <= tie_tolerance);
+        action_gaps[s] = others.iter().map(|&a| best - row[a]).reduce(f64::min).unwrap_or(0.0);
+        table[s] = optimal[0];
+        optimal_actions[s] = optimal;
+    }
+
+    QValueIteration {
+        q,
+        values,
+        action_gaps,
+        optimal_actions,
+        policy: DeterministicPolicy::from_vec(env, table),
+        stats,
+        is_terminal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::value_iteration::value_iteration;
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+    use crate::environments::line_world::line_world_dp;
+
+    #[test]
+    fn test_values_and_policy_match_value_iteration() {
+        let env = grid_world();
+        let (policy, values, _) = value_iteration(&env, 1e-10, 0.9, 10_000);
+        let result = q_value_iteration(&env, 1e-10, 0.9, 10_000, 1e-8);
+
+        for (v, w) in result.values.iter().zip(&values) {
+            assert!((v - w).abs() < 1e-8);
+        }
+        // value_iteration garde la première action maximale, qui est optimale
+        for s in 0..25 {
+            if s != 4 && s != 24 {
+                assert!(result.is_optimal(s, policy.policy_table[s]));
+            }
+        }
+        assert!(result.rmse(&result.q) == 0.0);
+    }
+
+    #[test]
+    fn test_ties_and_action_gaps() {
+        let env = grid_world();
+        let result = q_value_iteration(&env, 1e-12, 0.9, 10_000, 1e-9);
+
+        // En haut à gauche, bas et droite mènent aussi vite à la case gagnante
+        assert_eq!(result.optimal_actions[0], vec![1, 3]);
+        assert!(result.action_gaps[0] > 0.0);
+        // Juste à gauche de la case gagnante, seule la droite est optimale
+        assert_eq!(result.optimal_actions[23], vec![3]);
+        assert!(result.optimal_actions[4].is_empty());
+        assert_eq!(result.action_gaps[24], 0.0);
+
+        // Sur line world, l'écart vaut Q*(s, droite) - Q*(s, gauche)
+        let line = q_value_iteration(&line_world_dp(), 1e-12, 0.9, 10_000, 1e-9);
+        for s in 1..4 {
+            assert_eq!(line.optimal_actions[s], vec![1]);
+            let gap = line.q.get(s, 1) - line.q.get(s, 0);
+            assert!((line.action_gaps[s] - gap).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_rmse_against_a_learned_table() {
+        let result = q_value_iteration(&line_world_dp(), 1e-12, 0.9, 10_000, 1e-9);
+        let mut q = result.q.clone();
+        // Une seule erreur de 0.6 sur les 3 x 2 couples non terminaux
+        q.set(2, 0, q.get(2, 0) + 0.6);
+        assert!((result.rmse(&q) - (0.36f64 / 6.0).sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,105 @@
+//! Diagnostic de convergence renvoyé par les solveurs DP.
+
+use crate::algorithms::dp::sweeps::{greedy_backup, terminal_mask};
+use crate::core::envs::DynamicProgramingEnvironment;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Comment un solveur s'est arrêté
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolveReport {
+    /// Balayages (value iteration) ou étapes d'amélioration (policy iteration)
+    pub iterations: usize,
+    /// Écart max de chaque balayage, y compris ceux des évaluations de la policy iteration
+    pub delta_history: Vec<f64>,
+    /// Faux si le solveur s'est arrêté sur `max_iter`
+    pub converged: bool,
+    pub wall_time: Duration,
+    /// ε = ||T V - V||∞ pour les valeurs renvoyées
+    pub bellman_residual: f64,
+    /// 2γε / (1 - γ) : borne sur V* - V^π pour la policy gloutonne renvoyée (None si γ ≥ 1)
+    pub suboptimality_bound: Option<f64>,
+    /// Nombre d'états dont l'action a changé à chaque amélioration (vide pour la value iteration)
+    pub policy_changes: Vec<usize>,
+}
+
+impl SolveReport {
+    /// Complète le rapport avec le résidu de Bellman de `values` et la borne qui en découle
+    pub fn finish(
+        &mut self,
+        env: &dyn DynamicProgramingEnvironment,
+        values: &[f64],
+        gamma: f64,
+        wall_time: Duration,
+    ) {
+        self.bellman_residual = bellman_residual(env, values, gamma);
+        self.suboptimality_bound =
+            (gamma < 1.0).then(|| 2.0 * gamma * self.bellman_residual / (1.0 - gamma));
+        self.wall_time = wall_time;
+    }
+}
+
+impl Display for SolveReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} en {} itérations ({:?}), résidu de Bellman : {:e}",
+            if self.converged { "Convergé" } else { "Non convergé (max_iter atteint)" },
+            self.iterations,
+            self.wall_time,
+            self.bellman_residual
+        )?;
+        if let Some(bound) = self.suboptimality_bound {
+            write!(f, ", perte max de la policy : {:e}", bound)?;
+        }
+        Ok(())
+    }
+}
+
+/// ||T V - V||∞ sur les états non terminaux
+pub fn bellman_residual(env: &dyn DynamicProgramingEnvironment, values: &[f64], gamma: f64) -> f64 {
+    let is_terminal = terminal_mask(env);
+    (0..env.num_states())
+        .filter(|&s| !is_terminal[s])
+        .map(|s| (greedy_backup(env, s, values, gamma).1 - values[s]).abs())
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::dp::policy_iteration::policy_iteration;
+    use crate::algorithms::dp::value_iteration::value_iteration;
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+
+    #[test]
+    fn test_value_iteration_reports_whether_it_converged() {
+        let env = grid_world();
+        let (_, _, report) = value_iteration(&env, 1e-8, 0.9, 10_000);
+        assert!(report.converged);
+        assert_eq!(report.delta_history.len(), report.iterations);
+        assert!(*report.delta_history.last().unwrap() < 1e-8);
+        let bound = report.suboptimality_bound.unwrap();
+        assert!((bound - 2.0 * 0.9 * report.bellman_residual / 0.1).abs() < 1e-15);
+        assert!(report.policy_changes.is_empty());
+
+        // Arrêt sur max_iter : plus confondu avec une convergence
+        let (_, _, truncated) = value_iteration(&env, 1e-8, 0.9, 2);
+        assert!(!truncated.converged);
+        assert_eq!(truncated.iterations, 2);
+        assert!(truncated.bellman_residual > report.bellman_residual);
+    }
+
+    #[test]
+    fn test_policy_iteration_counts_policy_changes() {
+        let env = grid_world();
+        let (_, _, report) = policy_iteration(&env, 1e-8, 0.9, 100);
+        assert!(report.converged);
+        assert_eq!(report.policy_changes.len(), report.iterations);
+        assert!(report.policy_changes[0] > 0);
+        assert_eq!(*report.policy_changes.last().unwrap(), 0);
+        // Plusieurs balayages d'évaluation par itération
+        assert!(report.delta_history.len() > report.iterations);
+        assert!(report.suboptimality_bound.unwrap() < 1e-6);
+    }
+}
@@ -0,0 +1,341 @@
+//! Variantes de la programmation dynamique : balayages Jacobi ou Gauss-Seidel,
+//! policy iteration modifiée (k balayages d'évaluation) et value iteration asynchrone.
+
+use crate::algorithms::dp::report::SolveReport;
+use crate::core::envs::DynamicProgramingEnvironment;
+use crate::core::policies::DeterministicPolicy;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Mise à jour des valeurs pendant un balayage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SweepMode {
+    /// Synchrone : toutes les valeurs du balayage sont calculées à partir de l'ancien V
+    Jacobi,
+    /// En place : chaque mise à jour utilise les valeurs déjà recalculées du balayage
+    #[default]
+    GaussSeidel,
+}
+
+/// Ordre de visite des états de la value iteration asynchrone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StateOrder {
+    /// 0, 1, ..., n - 1 à chaque balayage
+    #[default]
+    Cyclic,
+    /// Une permutation aléatoire des états à chaque balayage
+    Random,
+}
+
+/// Options des variantes DP, sélectionnables depuis `Config`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DpOptions {
+    pub sweep: SweepMode,
+    /// Nombre de balayages d'évaluation par itération de la policy iteration modifiée
+    pub evaluation_sweeps: usize,
+    pub state_order: StateOrder,
+    /// Écart de Q* sous lequel deux actions sont considérées toutes deux optimales
+    pub tie_tolerance: f64,
+    /// Threads des balayages Jacobi parallèles (1 : séquentiel, 0 : tous les cœurs).
+    /// N'a d'effet qu'avec `sweep = Jacobi`, dont les résultats restent identiques.
+    pub threads: usize,
+}
+
+impl Default for DpOptions {
+    fn default() -> Self {
+        Self {
+            sweep: SweepMode::GaussSeidel,
+            evaluation_sweeps: 5,
+            state_order: StateOrder::Cyclic,
+            tie_tolerance: 1e-9,
+            threads: 1,
+        }
+    }
+}
+
+/// Coût d'une résolution, pour comparer les variantes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SweepStats {
+    /// Balayages complets des états
+    pub sweeps: usize,
+    /// Mises à jour d'état (backups de Bellman)
+    pub backups: usize,
+    /// Dernier écart max observé entre deux balayages (ou résidu de Bellman pour la PI modifiée)
+    pub residual: f64,
+}
+
+pub(crate) fn terminal_mask(env: &dyn DynamicProgramingEnvironment) -> Vec<bool> {
+    let mut is_terminal = vec![false; env.num_states()];
+    for ts in env.get_terminal_states() {
+        is_terminal[ts] = true;
+    }
+    is_terminal
+}
+
+pub(crate) fn q_value(
+    env: &dyn DynamicProgramingEnvironment,
+    s: usize,
+    a: usize,
+    values: &[f64],
+    gamma: f64,
+) -> f64 {
+    env.transitions_from(s, a)
+        .into_iter()
+        .map(|(s_prime, r_index, p)| p * (env.get_reward(r_index) + gamma * values[s_prime]))
+        .sum()
+}
+
+/// (meilleure action, max_a Q(s, a)), la première action en cas d'égalité
+pub(crate) fn greedy_backup(
+    env: &dyn DynamicProgramingEnvironment,
+    s: usize,
+    values: &[f64],
+    gamma: f64,
+) -> (usize, f64) {
+    let mut best = (0, f64::NEG_INFINITY);
+    for a in 0..env.num_actions() {
+        let q = q_value(env, s, a, values, gamma);
+        if q > best.1 {
+            best = (a, q);
+        }
+    }
+    best
+}
+
+/// Policy gloutonne par rapport à `values` (action 0 dans les états terminaux)
+pub(crate) fn greedy_policy(
+    env: &dyn DynamicProgramingEnvironment,
+    values: &[f64],
+    is_terminal: &[bool],
+    gamma: f64,
+) -> DeterministicPolicy {
+    let table = (0..env.num_states())
+        .map(|s| if is_terminal[s] { 0 } else { greedy_backup(env, s, values, gamma).0 })
+        .collect();
+    DeterministicPolicy::from_vec(env, table)
+}
+
+/// Un balayage de `backup` sur les états non terminaux, renvoie l'écart max
+fn sweep(
+    values: &mut Vec<f64>,
+    is_terminal: &[bool],
+    mode: SweepMode,
+    stats: &mut SweepStats,
+    backup: impl Fn(usize, &[f64]) -> f64,
+) -> f64 {
+    let mut delta: f64 = 0.0;
+    match mode {
+        SweepMode::Jacobi => {
+            let mut new_values = values.clone();
+            for s in (0..values.len()).filter(|&s| !is_terminal[s]) {
+                new_values[s] = backup(s, values);
+                delta = delta.max((new_values[s] - values[s]).abs());
+                stats.backups += 1;
+            }
+            *values = new_values;
+        }
+        SweepMode::GaussSeidel => {
+            for s in (0..values.len()).filter(|&s| !is_terminal[s]) {
+                let v = backup(s, values);
+                delta = delta.max((v - values[s]).abs());
+                values[s] = v;
+                stats.backups += 1;
+            }
+        }
+    }
+    stats.sweeps += 1;
+    stats.residual = delta;
+    delta
+}
+
+/// Value iteration avec balayages Jacobi ou Gauss-Seidel
+pub fn value_iteration_sweeps(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    mode: SweepMode,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats) {
+    let (policy, values, stats, _) =
+        value_iteration_sweeps_with_report(env, theta, gamma, max_iter, mode);
+    (policy, values, stats)
+}
+
+/// `value_iteration_sweeps`, avec en plus le rapport de convergence
+pub fn value_iteration_sweeps_with_report(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    mode: SweepMode,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats, SolveReport) {
+    let start = Instant::now();
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut stats = SweepStats::default();
+    let mut report = SolveReport::default();
+
+    for _ in 0..max_iter {
+        let delta = sweep(&mut values, &is_terminal, mode, &mut stats, |s, v| {
+            greedy_backup(env, s, v, gamma).1
+        });
+        report.iterations += 1;
+        report.delta_history.push(delta);
+        if delta < theta {
+            report.converged = true;
+            break;
+        }
+    }
+
+    report.finish(env, &values, gamma, start.elapsed());
+    (greedy_policy(env, &values, &is_terminal, gamma), values, stats, report)
+}
+
+/// Évaluation itérative de `policy` avec balayages Jacobi ou Gauss-Seidel
+pub fn policy_evaluation_sweeps(
+    env: &dyn DynamicProgramingEnvironment,
+    policy: &DeterministicPolicy,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    mode: SweepMode,
+) -> (Vec<f64>, SweepStats) {
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut stats = SweepStats::default();
+
+    for _ in 0..max_iter {
+        let delta = sweep(&mut values, &is_terminal, mode, &mut stats, |s, v| {
+            q_value(env, s, policy.policy_table[s], v, gamma)
+        });
+        if delta < theta {
+            break;
+        }
+    }
+    (values, stats)
+}
+
+/// Policy iteration modifiée : `evaluation_sweeps` balayages d'évaluation (partant des valeurs
+/// précédentes) entre deux améliorations. S'arrête quand le résidu de Bellman passe sous `theta`.
+pub fn modified_policy_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    evaluation_sweeps: usize,
+    mode: SweepMode,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats) {
+    assert!(evaluation_sweeps >= 1, "Il faut au moins un balayage d'évaluation");
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut table = vec![0; env.num_states()];
+    let mut stats = SweepStats::default();
+
+    for _ in 0..max_iter {
+        // Amélioration, qui donne aussi le résidu de Bellman ||T V - V||
+        let mut residual: f64 = 0.0;
+        for s in (0..env.num_states()).filter(|&s| !is_terminal[s]) {
+            let (a, q) = greedy_backup(env, s, &values, gamma);
+            residual = residual.max((q - values[s]).abs());
+            table[s] = a;
+        }
+        stats.residual = residual;
+        if residual < theta {
+            break;
+        }
+
+        // Évaluation partielle de la policy courante
+        for _ in 0..evaluation_sweeps {
+            sweep(&mut values, &is_terminal, mode, &mut stats, |s, v| {
+                q_value(env, s, table[s], v, gamma)
+            });
+        }
+        // Les balayages d'évaluation ont écrasé le résidu : on garde celui de Bellman
+        stats.residual = residual;
+    }
+
+    (greedy_policy(env, &values, &is_terminal, gamma), values, stats)
+}
+
+/// Value iteration asynchrone en place : les états sont mis à jour un par un, dans l'ordre
+/// cyclique ou selon une permutation aléatoire tirée à chaque balayage
+pub fn async_value_iteration(
+    env: &dyn DynamicProgramingEnvironment,
+    theta: f64,
+    gamma: f64,
+    max_iter: usize,
+    order: StateOrder,
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, Vec<f64>, SweepStats) {
+    let is_terminal = terminal_mask(env);
+    let mut values = vec![0.0; env.num_states()];
+    let mut states: Vec<usize> = (0..env.num_states()).filter(|&s| !is_terminal[s]).collect();
+    let mut stats = SweepStats::default();
+
+    for _ in 0..max_iter {
+        if order == StateOrder::Random {
+            states.shuffle(rng);
+        }
+        let mut delta: f64 = 0.0;
+        for &s in &states {
+            let v = greedy_backup(env, s, &values, gamma).1;
+            delta = delta.max((v - values[s]).abs());
+            values[s] = v;
+            stats.backups += 1;
+        }
+        stats.sweeps += 1;
+        stats.residual = delta;
+        if delta < theta {
+            break;
+        }
+    }
+
+    (greedy_policy(env, &values, &is_terminal, gamma), values, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::value_iteration::value_iteration;
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn assert_close(a: &[f64], b: &[f64], eps: f64) {
+        for (s, (x, y)) in a.iter().zip(b).enumerate() {
+            assert!((x - y).abs() < eps, "état {} : {} != {}", s, x, y);
+        }
+    }
+
+    #[test]
+    fn test_variants_agree_with_value_iteration() {
+        let env = grid_world();
+        let (reference_policy, reference, _) = value_iteration(&env, 1e-10, 0.9, 10_000);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let results = [
+            value_iteration_sweeps(&env, 1e-10, 0.9, 10_000, SweepMode::Jacobi),
+            value_iteration_sweeps(&env, 1e-10, 0.9, 10_000, SweepMode::GaussSeidel),
+            modified_policy_iteration(&env, 1e-10, 0.9, 10_000, 3, SweepMode::Jacobi),
+            modified_policy_iteration(&env, 1e-10, 0.9, 10_000, 3, SweepMode::GaussSeidel),
+            async_value_iteration(&env, 1e-10, 0.9, 10_000, StateOrder::Cyclic, &mut rng),
+            async_value_iteration(&env, 1e-10, 0.9, 10_000, StateOrder::Random, &mut rng),
+        ];
+        for (policy, values, stats) in &results {
+            assert_close(values, &reference, 1e-6);
+            assert_eq!(policy.policy_table, reference_policy.policy_table);
+            assert!(stats.residual < 1e-10);
+            assert_eq!(stats.backups, stats.sweeps * 23);
+        }
+    }
+
+    #[test]
+    fn test_gauss_seidel_needs_fewer_sweeps_than_jacobi() {
+        let env = grid_world();
+        let (_, _, jacobi) = value_iteration_sweeps(&env, 1e-8, 0.9, 10_000, SweepMode::Jacobi);
+        let (_, _, gs) = value_iteration_sweeps(&env, 1e-8, 0.9, 10_000, SweepMode::GaussSeidel);
+        assert!(gs.sweeps <= jacobi.sweeps, "GS {} > Jacobi {}", gs.sweeps, jacobi.sweeps);
+    }
+}
@@ -1,35 +1,42 @@
+use crate::algorithms::dp::report::SolveReport;
 use crate::core::envs::DynamicProgramingEnvironment;
 use crate::core::policies::DeterministicPolicy;
+use std::time::Instant;
 
+/// Value iteration en place ; le rapport indique si `theta` a été atteint avant `max_iter`
 pub fn value_iteration(
     env: &dyn DynamicProgramingEnvironment,
     theta: f64,
     gamma: f64,
     max_iter: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    let mut policy: DeterministicPolicy = DeterministicPolicy::new_det_pol(env);
+) -> (DeterministicPolicy, Vec<f64>, SolveReport) {
+    let start = Instant::now();
+    let mut report = SolveReport::default();
+    let mut policy: DeterministicPolicy = DeterministicPolicy::from_vec(env, vec![0; env.num_states()]);
     let mut values = vec![0.0; env.num_states()];
+    let mut is_terminal = vec![false; env.num_states()];
+    for ts in env.get_terminal_states() {
+        is_terminal[ts] = true;
+    }
 
-    for ep in 0..max_iter{
-        if ep % 100 == 0 {
-            println!("=== Iteration {} ===", ep);
-        }
-        
+    for _ in 0..max_iter {
         let mut delta: f64 = 0.0;
 
         for s in 0..env.num_states() {
+            // Les états terminaux gardent une valeur nulle
+            if is_terminal[s] {
+                policy.set_action(&s, 0);
+                continue;
+            }
             let value_old = values[s];
             let mut max_q = f64::NEG_INFINITY;
             let mut best_a: Option<usize> = None;
 
             for a in 0..env.num_actions() {
                 let mut q_s_a = 0.0;
-                for s_prime in 0..env.num_states() {
-                    for r_index in 0..env.num_rewards() {
-                        let r = env.get_reward(r_index);
-                        let p = env.get_transition_prob(s, a, s_prime, r_index);
-                        q_s_a += p * (r + gamma * values[s_prime]);
-                    }
+                for (s_prime, r_index, p) in env.transitions_from(s, a) {
+                    let r = env.get_reward(r_index);
+                    q_s_a += p * (r + gamma * values[s_prime]);
                 }
 
                 if q_s_a > max_q {
@@ -43,11 +50,16 @@ pub fn value_iteration(
             policy.set_action(&s, best_a.unwrap());
         }
 
+        report.iterations += 1;
+        report.delta_history.push(delta);
         if delta < theta {
-            return (policy, values);
+            report.converged = true;
+            break;
         }
     }
-    (policy, values)
+
+    report.finish(env, &values, gamma, start.elapsed());
+    (policy, values, report)
 }
 
 #[cfg(test)]
@@ -61,7 +73,7 @@ mod tests {
         let expected = vec![0, 1, 1, 1, 0];
 
         for _ in 0..50 {
-            let (policy, values) = value_iteration(&env, 0.0001, 0.99, 1000);
+            let (policy, values, _) = value_iteration(&env, 0.0001, 0.99, 1000);
 
             println!("Values : {:?}", values);
             println!("Policy : {}", policy);
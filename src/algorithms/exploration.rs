@@ -0,0 +1,425 @@
+use crate::algorithms::schedule::Schedule;
+use crate::core::policies::{mask_from_available, restrict_to_mask};
+use crate::core::q_table::QTable;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+type State = usize;
+type Action = usize;
+
+/// Stratégie d'exploration des algorithmes de contrôle tabulaires (TD, MC on-policy, Dyna).
+///
+/// L'algorithme appelle `initialize` sur sa Q-table, `begin_episode` au début de chaque épisode,
+/// `select` à chaque pas, puis `observe` une fois l'action jouée, et ajoute `intrinsic_reward`
+/// à la récompense de la mise à jour.
+pub trait ExplorationStrategy {
+    /// Distribution de la policy de comportement sur les actions de `actions`
+    /// (vecteur de taille `q.num_actions()`, nul hors de `actions`)
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64>;
+
+    fn select(&mut self, q: &QTable, s: State, actions: &[Action], rng: &mut dyn RngCore) -> Action {
+        let probs = self.action_probs(q, s, actions);
+        let mut u = rng.random::<f64>();
+        for &a in actions {
+            u -= probs[a];
+            if u < 0.0 {
+                return a;
+            }
+        }
+        // Erreurs d'arrondi : dernière action de probabilité non nulle
+        *actions
+            .iter()
+            .rev()
+            .find(|&&a| probs[a] > 0.0)
+            .unwrap_or(&actions[0])
+    }
+
+    /// Enregistre la visite de (s, a)
+    fn observe(&mut self, _s: State, _a: Action) {}
+
+    /// Bonus ajouté à la récompense de la transition depuis (s, a)
+    fn intrinsic_reward(&self, _s: State, _a: Action) -> f64 {
+        0.0
+    }
+
+    /// Prépare la Q-table avant le premier épisode
+    fn initialize(&self, _q: &mut QTable) {}
+
+    /// Début de l'épisode `episode` (à partir de 0), pour les paramètres planifiés
+    fn begin_episode(&mut self, _episode: usize) {}
+
+    /// Epsilon effectif dans l'état s, pour les stratégies qui en ont un
+    fn epsilon(&self, _s: State) -> Option<f64> {
+        None
+    }
+}
+
+/// Meilleure action de `actions` selon `score` (la dernière en cas d'égalité, comme `QTable::argmax`)
+fn argmax_by(actions: &[Action], score: impl Fn(Action) -> f64) -> Action {
+    let mut best = actions[0];
+    for &a in actions {
+        if score(a) >= score(best) {
+            best = a;
+        }
+    }
+    best
+}
+
+fn one_hot(num_actions: usize, a: Action) -> Vec<f64> {
+    let mut probs = vec![0.0; num_actions];
+    probs[a] = 1.0;
+    probs
+}
+
+/// Compteurs de visites N(s) et N(s, a)
+#[derive(Debug, Default, Clone)]
+pub struct VisitCounts {
+    state: HashMap<State, u64>,
+    state_action: HashMap<(State, Action), u64>,
+}
+
+impl VisitCounts {
+    pub fn visit(&mut self, s: State, a: Action) {
+        *self.state.entry(s).or_insert(0) += 1;
+        *self.state_action.entry((s, a)).or_insert(0) += 1;
+    }
+
+    pub fn state(&self, s: State) -> u64 {
+        self.state.get(&s).copied().unwrap_or(0)
+    }
+
+    pub fn state_action(&self, s: State, a: Action) -> u64 {
+        self.state_action.get(&(s, a)).copied().unwrap_or(0)
+    }
+}
+
+/// Action gloutonne avec probabilité 1 - ε, action uniforme sinon.
+/// ε peut suivre un `Schedule` ; avec `Schedule::VisitCount`, ε = 1 / N(s) (GLIE).
+#[derive(Debug, Clone)]
+pub struct EpsilonGreedy {
+    pub schedule: Schedule,
+    episode: usize,
+    counts: VisitCounts,
+}
+
+impl EpsilonGreedy {
+    pub fn new(epsilon: f64) -> Self {
+        assert!((0.0..=1.0).contains(&epsilon), "epsilon doit être dans [0, 1]");
+        Self::scheduled(Schedule::Constant(epsilon))
+    }
+
+    pub fn scheduled(schedule: Schedule) -> Self {
+        Self {
+            schedule,
+            episode: 0,
+            counts: VisitCounts::default(),
+        }
+    }
+
+    fn current_epsilon(&self, s: State) -> f64 {
+        self.schedule
+            .value(self.episode, self.counts.state(s))
+            .clamp(0.0, 1.0)
+    }
+}
+
+impl ExplorationStrategy for EpsilonGreedy {
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64> {
+        let epsilon = self.current_epsilon(s);
+        let mask = mask_from_available(q.num_actions(), actions);
+        let greedy = q.argmax(s, &mask).unwrap_or(actions[0]);
+        let base = epsilon / actions.len() as f64;
+        let mut probs: Vec<f64> = mask.iter().map(|&m| if m { base } else { 0.0 }).collect();
+        probs[greedy] += 1.0 - epsilon;
+        probs
+    }
+
+    fn observe(&mut self, s: State, a: Action) {
+        self.counts.visit(s, a);
+    }
+
+    fn begin_episode(&mut self, episode: usize) {
+        self.episode = episode;
+    }
+
+    fn epsilon(&self, s: State) -> Option<f64> {
+        Some(self.current_epsilon(s))
+    }
+}
+
+/// Softmax (Boltzmann) sur Q(s, ·) : π(a|s) ∝ exp(Q(s, a) / τ)
+#[derive(Debug, Clone)]
+pub struct Boltzmann {
+    pub temperature: f64,
+}
+
+impl Boltzmann {
+    pub fn new(temperature: f64) -> Self {
+        assert!(temperature > 0.0, "La température doit être strictement positive");
+        Self { temperature }
+    }
+}
+
+impl ExplorationStrategy for Boltzmann {
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64> {
+        let row = q.row(s);
+        let max = actions.iter().map(|&a| row[a]).fold(f64::NEG_INFINITY, f64::max);
+        let mut probs = vec![0.0; q.num_actions()];
+        for &a in actions {
+            probs[a] = ((row[a] - max) / self.temperature).exp();
+        }
+        restrict_to_mask(probs, &mask_from_available(q.num_actions(), actions))
+    }
+}
+
+/// UCB1 : argmax Q(s, a) + c √(ln N(s) / N(s, a)), les actions jamais essayées d'abord
+#[derive(Debug, Clone)]
+pub struct Ucb {
+    pub c: f64,
+    counts: VisitCounts,
+}
+
+impl Ucb {
+    pub fn new(c: f64) -> Self {
+        Self {
+            c,
+            counts: VisitCounts::default(),
+        }
+    }
+
+    fn score(&self, q: &QTable, s: State, a: Action) -> f64 {
+        let n_sa = self.counts.state_action(s, a);
+        if n_sa == 0 {
+            return f64::INFINITY;
+        }
+        let n_s = self.counts.state(s) as f64;
+        q.get(s, a) + self.c * (n_s.ln() / n_sa as f64).sqrt()
+    }
+}
+
+impl ExplorationStrategy for Ucb {
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64> {
+        // Parmi les actions jamais essayées, la première
+        let best = match actions.iter().find(|&&a| self.counts.state_action(s, a) == 0) {
+            Some(&a) => a,
+            None => argmax_by(actions, |a| self.score(q, s, a)),
+        };
+        one_hot(q.num_actions(), best)
+    }
+
+    fn observe(&mut self, s: State, a: Action) {
+        self.counts.visit(s, a);
+    }
+}
+
+/// Initialisation optimiste : Q(s, a) = `initial_value` partout, puis action gloutonne
+#[derive(Debug, Clone)]
+pub struct OptimisticInit {
+    pub initial_value: f64,
+}
+
+impl OptimisticInit {
+    pub fn new(initial_value: f64) -> Self {
+        Self { initial_value }
+    }
+}
+
+impl ExplorationStrategy for OptimisticInit {
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64> {
+        let mask = mask_from_available(q.num_actions(), actions);
+        one_hot(q.num_actions(), q.argmax(s, &mask).unwrap_or(actions[0]))
+    }
+
+    fn initialize(&self, q: &mut QTable) {
+        q.fill(self.initial_value);
+    }
+}
+
+/// Bonus intrinsèque β / √N(s, a) ajouté à la récompense, avec une policy ε-greedy
+#[derive(Debug, Clone)]
+pub struct CountBonus {
+    pub beta: f64,
+    inner: EpsilonGreedy,
+    counts: VisitCounts,
+}
+
+impl CountBonus {
+    pub fn new(beta: f64, epsilon: f64) -> Self {
+        Self::scheduled(beta, Schedule::Constant(epsilon))
+    }
+
+    pub fn scheduled(beta: f64, epsilon: Schedule) -> Self {
+        Self {
+            beta,
+            inner: EpsilonGreedy::scheduled(epsilon),
+            counts: VisitCounts::default(),
+        }
+    }
+}
+
+impl ExplorationStrategy for CountBonus {
+    fn action_probs(&self, q: &QTable, s: State, actions: &[Action]) -> Vec<f64> {
+        self.inner.action_probs(q, s, actions)
+    }
+
+    fn observe(&mut self, s: State, a: Action) {
+        self.counts.visit(s, a);
+        self.inner.observe(s, a);
+    }
+
+    fn begin_episode(&mut self, episode: usize) {
+        self.inner.begin_episode(episode);
+    }
+
+    fn epsilon(&self, s: State) -> Option<f64> {
+        self.inner.epsilon(s)
+    }
+
+    fn intrinsic_reward(&self, s: State, a: Action) -> f64 {
+        let n = self.counts.state_action(s, a).max(1) as f64;
+        self.beta / n.sqrt()
+    }
+}
+
+/// Choix de la stratégie dans `Config`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Exploration {
+    /// ε-greedy avec le schedule d'epsilon de `Config`
+    #[default]
+    EpsilonGreedy,
+    Boltzmann { temperature: f64 },
+    Ucb { c: f64 },
+    Optimistic { initial_value: f64 },
+    /// Bonus de comptage, avec ε-greedy sur le schedule d'epsilon de `Config`
+    CountBonus { beta: f64 },
+}
+
+impl Exploration {
+    pub fn build(&self, epsilon: Schedule) -> Box<dyn ExplorationStrategy> {
+        match *self {
+            Exploration::EpsilonGreedy => Box::new(EpsilonGreedy::scheduled(epsilon)),
+            Exploration::Boltzmann { temperature } => Box::new(Boltzmann::new(temperature)),
+            Exploration::Ucb { c } => Box::new(Ucb::new(c)),
+            Exploration::Optimistic { initial_value } => {
+                Box::new(OptimisticInit::new(initial_value))
+            }
+            Exploration::CountBonus { beta } => Box::new(CountBonus::scheduled(beta, epsilon)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn q_table() -> QTable {
+        let mut q = QTable::dense(1, 3);
+        q.set(0, 0, 1.0);
+        q.set(0, 1, 2.0);
+        q.set(0, 2, 0.0);
+        q
+    }
+
+    #[test]
+    fn test_epsilon_greedy_and_boltzmann_probs() {
+        let q = q_table();
+        let probs = EpsilonGreedy::new(0.3).action_probs(&q, 0, &[0, 1, 2]);
+        assert!((probs[1] - 0.8).abs() < 1e-12);
+        assert!((probs[0] - 0.1).abs() < 1e-12);
+
+        // Action 1 indisponible : la masse va à la meilleure action restante
+        let probs = EpsilonGreedy::new(0.0).action_probs(&q, 0, &[0, 2]);
+        assert_eq!(probs, vec![1.0, 0.0, 0.0]);
+
+        let probs = Boltzmann::new(1.0).action_probs(&q, 0, &[0, 1, 2]);
+        assert!((probs.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probs[1] > probs[0] && probs[0] > probs[2]);
+        let cold = Boltzmann::new(0.01).action_probs(&q, 0, &[0, 1, 2]);
+        assert!(cold[1] > 0.999);
+    }
+
+    #[test]
+    fn test_ucb_tries_every_action_then_uses_bonus() {
+        let q = q_table();
+        let mut ucb = Ucb::new(10.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut first = Vec::new();
+        for _ in 0..3 {
+            let a = ucb.select(&q, 0, &[0, 1, 2], &mut rng);
+            ucb.observe(0, a);
+            first.push(a);
+        }
+        first.sort();
+        assert_eq!(first, vec![0, 1, 2]);
+
+        // Avec un gros bonus, l'action la moins visitée repasse devant
+        for _ in 0..5 {
+            ucb.observe(0, 1);
+        }
+        assert_ne!(ucb.select(&q, 0, &[0, 1, 2], &mut rng), 1);
+    }
+
+    #[test]
+    fn test_optimistic_init_and_count_bonus() {
+        let mut q = QTable::sparse(4, 2);
+        OptimisticInit::new(5.0).initialize(&mut q);
+        assert_eq!(q.get(3, 1), 5.0);
+
+        let mut bonus = CountBonus::new(1.0, 0.1);
+        bonus.observe(0, 0);
+        assert_eq!(bonus.intrinsic_reward(0, 0), 1.0);
+        for _ in 0..3 {
+            bonus.observe(0, 0);
+        }
+        assert_eq!(bonus.intrinsic_reward(0, 0), 0.5);
+    }
+
+    #[test]
+    fn test_epsilon_greedy_visits_every_action_during_training() {
+        use crate::algorithms::schedule::Schedules;
+        use crate::algorithms::td::sarsa::sarsa;
+        use crate::environments::line_world::LineWorld;
+
+        let mut env = LineWorld::new();
+        let mut schedules = Schedules::constant(0.1);
+        let mut rng = StdRng::seed_from_u64(42);
+        sarsa(&mut env, &mut schedules, 0.9, &mut EpsilonGreedy::new(0.2), 200, &mut rng);
+
+        // Même les actions dominées (aller à gauche) sont essayées dans chaque état non terminal
+        for s in 1..4 {
+            for a in 0..2 {
+                assert!(schedules.visits(s, a) > 0, "({}, {}) jamais visité", s, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_strategy_drives_q_learning_on_line_world() {
+        use crate::algorithms::schedule::Schedules;
+        use crate::algorithms::td::q_learning::q_learning;
+        use crate::environments::line_world::LineWorld;
+
+        let strategies = [
+            Exploration::EpsilonGreedy,
+            Exploration::Boltzmann { temperature: 0.5 },
+            Exploration::Ucb { c: 1.0 },
+            Exploration::Optimistic { initial_value: 2.0 },
+            Exploration::CountBonus { beta: 0.1 },
+        ];
+        for exploration in strategies {
+            let mut env = LineWorld::new();
+            let mut strategy = exploration.build(Schedule::Constant(0.1));
+            let mut schedules = Schedules::constant(0.1);
+            let mut rng = StdRng::seed_from_u64(0);
+            let (policy, _, rewards) =
+                q_learning(&mut env, &mut schedules, 0.9, &mut *strategy, 300, &mut rng);
+            assert_eq!(rewards.len(), 300);
+            for s in 1..4 {
+                assert_eq!(policy.get_action(&s), 1, "{:?} : mauvaise action en {}", exploration, s);
+            }
+        }
+    }
+}
@@ -1,23 +1,23 @@
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-use rand::rngs::StdRng;
+use crate::core::q_table::QTable;
 use rand::seq::IndexedRandom;
-use rand::SeedableRng;
+use rand::RngCore;
 
 /// Monte Carlo Exploring Starts control with tracking of total reward per episode
 pub fn monte_carlo_es(
     env: &mut dyn MonteCarloEnvironment,
     episodes: usize,
     gamma: f64,
-) -> (DeterministicPolicy, Vec<Vec<f64>>, Vec<f64>) {
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
     let num_states = env.num_states();
     let num_actions = env.num_actions();
-    let mut q = vec![vec![0.0; num_actions]; num_states];
+    let mut q = QTable::for_env(&*env);
     let mut returns_count = vec![vec![0; num_actions]; num_states];
-    let mut policy = DeterministicPolicy::new_det_pol(env);
+    let mut policy = DeterministicPolicy::new_det_pol(env, rng);
     // Vector to store total (undiscounted) reward per episode
     let mut rewards_per_episode = Vec::with_capacity(episodes);
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
 
     for ep in 0..episodes {
         if ep % 100 == 0 {
@@ -32,7 +32,7 @@ pub fn monte_carlo_es(
         // Génération de l'épisode
         while !env.is_game_over() {
             let s = env.state_id();
-            let a = *env.available_actions().choose(&mut rng).unwrap();
+            let a = *env.available_actions().choose(rng).unwrap();
             env.step(a);
             let r = env.score();
             episode.push((s, a, r));
@@ -51,13 +51,12 @@ pub fn monte_carlo_es(
                 visited.push((s, a));
                 returns_count[s][a] += 1;
                 let alpha = 1.0 / returns_count[s][a] as f64;
-                q[s][a] += alpha * (g - q[s][a]);
+                let q_sa = q.get_mut(s, a);
+                *q_sa += alpha * (g - *q_sa);
 
                 // Mise à jour de la politique
-                let best_action = (0..num_actions)
-                    .filter(|&a| !env.is_forbidden(a))
-                    .max_by(|&a1, &a2| q[s][a1].partial_cmp(&q[s][a2]).unwrap())
-                    .unwrap();
+                let allowed: Vec<bool> = (0..num_actions).map(|a| !env.is_forbidden(a)).collect();
+                let best_action = q.argmax(s, &allowed).unwrap();
                 policy.set_action(&s, best_action);
             }
         }
@@ -1,3 +1,4 @@
 pub mod mc_es;
 pub mod off_policy;
 pub mod on_policy_first_visit;
+pub mod prediction;
@@ -1,7 +1,7 @@
 use crate::core::envs::MonteCarloEnvironment;
-use crate::core::policies::DeterministicPolicy;
-use rand::prelude::{IndexedRandom, StdRng};
-use rand::{Rng, SeedableRng};
+use crate::core::policies::{mask_from_available, DeterministicPolicy, EpsilonGreedyPolicy, Policy};
+use crate::core::q_table::QTable;
+use rand::RngCore;
 
 /// Off-policy Monte Carlo Control with Importance Sampling and tracking total reward per episode
 pub fn off_policy_mc_control(
@@ -9,14 +9,14 @@ pub fn off_policy_mc_control(
     episodes: usize,
     gamma: f64,
     epsilon_behavior: f64,
-) -> (DeterministicPolicy, Vec<Vec<f64>>, Vec<f64>) {
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
     let num_states = env.num_states();
     let num_actions = env.num_actions();
-    let mut q = vec![vec![0.0; num_actions]; num_states];
+    let mut q = QTable::for_env(&*env);
     let mut c = vec![vec![0.0; num_actions]; num_states];
-    let mut policy = DeterministicPolicy::new_det_pol(env);
+    let mut policy = DeterministicPolicy::new_det_pol(env, rng);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
 
     for ep in 0..episodes {
         if ep % 1000 == 0 {
@@ -26,22 +26,17 @@ pub fn off_policy_mc_control(
         let mut episode = Vec::new();
         let mut total_reward = 0.0;
 
-        // Génération de l'épisode suivant la politique de comportement eps-greedy
+        // Génération de l'épisode suivant la politique de comportement eps-greedy,
+        // en retenant b(a|s) pour les ratios d'importance
+        let behavior = EpsilonGreedyPolicy::new(&policy, epsilon_behavior);
         while !env.is_game_over() {
             let s = env.state_id();
-            let a = if rng.random::<f64>() < epsilon_behavior {
-                *env.available_actions().choose(&mut rng).unwrap()
-            } else {
-                // Si l'action de la policy est forbidden, choisi une action aléatoire
-                let a = policy.get_action(&s);
-                if env.is_forbidden(a) {
-                    *env.available_actions().choose(&mut rng).unwrap()
-                }
-                else { a }
-            };
+            let available = env.available_actions();
+            let a = behavior.get_action_masked(s, &available, rng);
+            let b = behavior.action_probs(s, &mask_from_available(num_actions, &available))[a];
             env.step(a);
             let r = env.score();
-            episode.push((s, a, r));
+            episode.push((s, a, r, b));
             total_reward += r;
         }
 
@@ -51,23 +46,23 @@ pub fn off_policy_mc_control(
         // Mise à jour off-policy par importance sampling
         let mut g = 0.0;
         let mut w = 1.0;
-        for &(s, a, r) in episode.iter().rev() {
+        for &(s, a, r, b) in episode.iter().rev() {
             g = gamma * g + r;
             c[s][a] += w;
             let alpha = w / c[s][a];
-            q[s][a] += alpha * (g - q[s][a]);
+            let q_sa = q.get_mut(s, a);
+            *q_sa += alpha * (g - *q_sa);
 
             // Mise à jour de la politique cible
-            let best_action = (0..num_actions)
-                .filter(|&a| !env.is_forbidden(a))
-                .max_by(|&a1, &a2| q[s][a1].partial_cmp(&q[s][a2]).unwrap())
-                .unwrap();
+            let allowed: Vec<bool> = (0..num_actions).map(|a| !env.is_forbidden(a)).collect();
+            let best_action = q.argmax(s, &allowed).unwrap();
             policy.set_action(&s, best_action);
 
             if a != best_action {
                 break;
             }
-            w /= epsilon_behavior / ((1.0 - epsilon_behavior) / (num_actions as f64 - 1.0));
+            // π(a|s) = 1 pour la politique cible gloutonne
+            w /= b;
         }
     }
 
@@ -77,6 +72,8 @@ pub fn off_policy_mc_control(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use crate::core::envs::Environment;
     use crate::environments::line_world::LineWorld;
 
@@ -84,18 +81,16 @@ mod tests {
     fn test_off_policy_mc_control_learns() {
         let mut env = LineWorld::new();
 
-        let (policy, q, rewards) = off_policy_mc_control(&mut env, 10_000, 0.9, 0.1);
+        let (policy, q, rewards) = off_policy_mc_control(&mut env, 10_000, 0.9, 0.1, &mut StdRng::seed_from_u64(0));
         assert_eq!(rewards.len(), 10_000);
 
         // Export CSV des Q-values
         use std::fs::File;
         use std::io::{BufWriter, Write};
-        let mut file = BufWriter::new(File::create("q_values_off_policy.csv").unwrap());
+        let mut file = BufWriter::new(File::create(std::env::temp_dir().join("q_values_off_policy.csv")).unwrap());
         writeln!(file, "state,action,q_value").unwrap();
-        for (s, actions) in q.iter().enumerate() {
-            for (a, &q_val) in actions.iter().enumerate() {
-                writeln!(file, "{},{},{}", s, a, q_val).unwrap();
-            }
+        for (s, a, q_val) in q.entries() {
+            writeln!(file, "{},{},{}", s, a, q_val).unwrap();
         }
 
         // Vérification politique
@@ -106,15 +101,13 @@ mod tests {
         }
 
         // Q-values finies
-        for actions in &q {
-            for &value in actions {
-                assert!(value.is_finite());
-            }
+        for (_, _, value) in q.entries() {
+            assert!(value.is_finite());
         }
 
         // Q-value élevée à un état positif
         let s = 3;
         let best = policy.get_action(&s);
-        assert!(q[s][best] > 0.5, "Q-value trop faible: {}", q[s][best]);
+        assert!(q.get(s, best) > 0.5, "Q-value trop faible: {}", q.get(s, best));
     }
 }
@@ -1,48 +1,47 @@
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-use rand::prelude::{IndexedRandom, StdRng};
-use rand::{Rng, SeedableRng};
+use crate::core::q_table::QTable;
+use rand::RngCore;
 
 /// On-policy First-Visit Monte Carlo Control with tracking of total reward per episode
+/// (`Schedule::VisitCount` pour alpha donne la moyenne empirique des retours)
 pub fn on_policy_first_visit_mc_control(
     env: &mut dyn MonteCarloEnvironment,
     episodes: usize,
     gamma: f64,
-    epsilon: f64,
-) -> (DeterministicPolicy, Vec<Vec<f64>>, Vec<f64>) {
-    let num_states = env.num_states();
+    schedules: &mut Schedules,
+    exploration: &mut dyn ExplorationStrategy,
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
     let num_actions = env.num_actions();
-    let mut q = vec![vec![0.0; num_actions]; num_states];
-    let mut returns_count = vec![vec![0; num_actions]; num_states];
-    let mut policy = DeterministicPolicy::new_det_pol(env);
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
+    let mut policy = DeterministicPolicy::new_det_pol(env, rng);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
 
     for ep in 0..episodes {
         if ep % 1000 == 0 {
             println!("=== Épisode {} ===", ep);
         }
+        schedules.begin_episode(ep);
+        exploration.begin_episode(ep);
         env.reset();
         let mut episode = Vec::new();
         let mut total_reward = 0.0;
 
-        // Génération de l'épisode suivant la politique ε-greedy
+        // Génération de l'épisode suivant la stratégie d'exploration sur Q
         while !env.is_game_over() {
             let s = env.state_id();
-            let a = if rng.random::<f64>() < epsilon {
-                *env.available_actions().choose(&mut rng).unwrap()
-            } else {
-                // Si l'action de la policy est forbidden, choisi une action aléatoire
-                let a = policy.get_action(&s);
-                if env.is_forbidden(a) {
-                    *env.available_actions().choose(&mut rng).unwrap()
-                } else {
-                    a
-                }
-            };
+            let a = exploration.select(&q, s, &env.available_actions(), rng);
+            schedules.record_epsilon(exploration.epsilon(s));
+            exploration.observe(s, a);
             env.step(a);
             let r = env.score();
-            episode.push((s, a, r));
+            let bonus = exploration.intrinsic_reward(s, a);
+            // Le bonus d'exploration entre dans le retour, pas dans la récompense journalisée
+            episode.push((s, a, r + bonus));
             total_reward += r;
         }
 
@@ -56,18 +55,18 @@ pub fn on_policy_first_visit_mc_control(
             g = gamma * g + r;
             // Vérifier première visite
             if !episode[..i].iter().any(|&(s2, a2, _)| s2 == s && a2 == a) {
-                returns_count[s][a] += 1;
-                let alpha = 1.0 / returns_count[s][a] as f64;
-                q[s][a] += alpha * (g - q[s][a]);
+                schedules.visit(s, a);
+                let alpha = schedules.alpha(s, a);
+                let q_sa = q.get_mut(s, a);
+                *q_sa += alpha * (g - *q_sa);
 
                 // Mise à jour de la politique greedy
-                let best_action = (0..num_actions)
-                    .filter(|&a| !env.is_forbidden(a))
-                    .max_by(|&a1, &a2| q[s][a1].partial_cmp(&q[s][a2]).unwrap())
-                    .unwrap();
+                let allowed: Vec<bool> = (0..num_actions).map(|a| !env.is_forbidden(a)).collect();
+                let best_action = q.argmax(s, &allowed).unwrap();
                 policy.set_action(&s, best_action);
             }
         }
+        schedules.end_episode();
     }
 
     (policy, q, rewards_per_episode)
@@ -76,6 +75,10 @@ pub fn on_policy_first_visit_mc_control(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::algorithms::exploration::EpsilonGreedy;
+    use crate::algorithms::schedule::Schedule;
     use crate::core::envs::Environment;
     use crate::environments::line_world::LineWorld;
 
@@ -83,13 +86,20 @@ mod tests {
     fn test_on_policy_first_visit_mc_control_learns() {
         let mut env = LineWorld::new();
 
-        let (policy, q, rewards) = on_policy_first_visit_mc_control(&mut env, 10_000, 0.9, 0.1);
+        let (policy, q, rewards) = on_policy_first_visit_mc_control(
+            &mut env,
+            10_000,
+            0.9,
+            &mut Schedules::new(Schedule::VisitCount),
+            &mut EpsilonGreedy::new(0.1),
+            &mut StdRng::seed_from_u64(0),
+        );
         assert_eq!(rewards.len(), 10_000);
 
         // Export CSV des récompenses
         use std::fs::File;
         use std::io::{BufWriter, Write};
-        let mut file = BufWriter::new(File::create("rewards_on_policy.csv").unwrap());
+        let mut file = BufWriter::new(File::create(std::env::temp_dir().join("rewards_on_policy.csv")).unwrap());
         writeln!(file, "episode,total_reward").unwrap();
         for (i, &r) in rewards.iter().enumerate() {
             writeln!(file, "{},{}", i, r).unwrap();
@@ -101,15 +111,13 @@ mod tests {
             let a = policy.get_action(&s);
             assert!(a < env.num_actions());
         }
-        for actions in &q {
-            for &value in actions {
-                assert!(value.is_finite());
-            }
+        for (_, _, value) in q.entries() {
+            assert!(value.is_finite());
         }
 
         // Q-value élevée à un état positif
         let s = 3;
         let best = policy.get_action(&s);
-        assert!(q[s][best] > 0.5, "Q-value trop faible: {}", q[s][best]);
+        assert!(q.get(s, best) > 0.5, "Q-value trop faible: {}", q.get(s, best));
     }
 }
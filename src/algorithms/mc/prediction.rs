@@ -0,0 +1,264 @@
+//! Prédiction Monte Carlo : estimation de V^π et Q^π d'une policy fixée à partir
+//! d'épisodes simulés uniquement, sans modèle de l'environnement.
+
+use crate::algorithms::dp::exact_evaluation::PolicyEvaluation;
+use crate::algorithms::schedule::Schedule;
+use crate::core::envs::MonteCarloEnvironment;
+use crate::core::policies::Policy;
+use crate::core::q_table::QTable;
+use rand::RngCore;
+use std::collections::HashSet;
+
+/// Quels retours d'un épisode sont utilisés pour un état (ou un couple état-action)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitMode {
+    /// Seulement le retour de la première visite : échantillons indépendants, estimateur sans biais
+    FirstVisit,
+    /// Le retour de chaque visite : plus d'échantillons par épisode, biaisé mais consistant
+    EveryVisit,
+}
+
+/// Estimations de la prédiction Monte Carlo
+#[derive(Debug)]
+pub struct McPrediction {
+    pub values: Vec<f64>,
+    pub q: QTable,
+    /// Nombre de retours utilisés pour chaque état
+    pub state_visits: Vec<u64>,
+    /// RMSE de `values` par rapport à la vérité après chaque épisode (vide sans vérité)
+    pub value_rmse: Vec<f64>,
+    /// RMSE de `q` par rapport à la vérité après chaque épisode (vide sans vérité)
+    pub q_rmse: Vec<f64>,
+}
+
+/// Estime V^π et Q^π en suivant `policy` pendant `episodes` épisodes.
+///
+/// `alpha` donne le pas de mise à jour : `Schedule::VisitCount` donne la moyenne empirique
+/// incrémentale des retours, `Schedule::Constant(α)` une moyenne mobile exponentielle (utile si
+/// l'environnement dérive). Si `truth` est fourni (par exemple `exact_policy_evaluation`), la
+/// RMSE des estimations est relevée à la fin de chaque épisode.
+#[allow(clippy::too_many_arguments)]
+pub fn mc_prediction(
+    env: &mut dyn MonteCarloEnvironment,
+    policy: &dyn Policy,
+    episodes: usize,
+    gamma: f64,
+    visits: VisitMode,
+    alpha: &Schedule,
+    truth: Option<&PolicyEvaluation>,
+    rng: &mut dyn RngCore,
+) -> McPrediction {
+    let num_states = env.num_states();
+    let num_actions = env.num_actions();
+    let mut values = vec![0.0; num_states];
+    let mut q = QTable::dense(num_states, num_actions);
+    let mut state_visits = vec![0u64; num_states];
+    let mut pair_visits = vec![0u64; num_states * num_actions];
+    let mut value_rmse = Vec::new();
+    let mut q_rmse = Vec::new();
+    if let Some(truth) = truth {
+        assert_eq!(truth.values.len(), num_states, "La vérité n'a pas le nombre d'états de l'env");
+        assert_eq!(
+            (truth.q.num_states(), truth.q.num_actions()),
+            (num_states, num_actions),
+            "La Q-table de la vérité n'a pas les dimensions de l'env"
+        );
+    }
+
+    for ep in 0..episodes {
+        env.reset();
+        let mut episode = Vec::new();
+
+        // Génération de l'épisode en suivant la policy évaluée
+        let mut t = 0;
+        while !env.is_game_over() {
+            let s = env.state_id();
+            let a = policy.get_action_masked_at(s, t, &env.available_actions(), rng);
+            let (_, r) = env.step(a);
+            episode.push((s, a, r));
+            t += 1;
+        }
+
+        // Pas de première visite de chaque état et de chaque couple
+        let mut first_state = vec![false; episode.len()];
+        let mut first_pair = vec![false; episode.len()];
+        if visits == VisitMode::FirstVisit {
+            let mut seen_states = HashSet::new();
+            let mut seen_pairs = HashSet::new();
+            for (i, &(s, a, _)) in episode.iter().enumerate() {
+                first_state[i] = seen_states.insert(s);
+                first_pair[i] = seen_pairs.insert((s, a));
+            }
+        }
+
+        let mut g = 0.0;
+        for (i, &(s, a, r)) in episode.iter().enumerate().rev() {
+            g = gamma * g + r;
+            if visits == VisitMode::EveryVisit || first_state[i] {
+                state_visits[s] += 1;
+                let step = alpha.value(ep, state_visits[s]);
+                values[s] += step * (g - values[s]);
+            }
+            if visits == VisitMode::EveryVisit || first_pair[i] {
+                let n = &mut pair_visits[s * num_actions + a];
+                *n += 1;
+                let step = alpha.value(ep, *n);
+                let q_sa = q.get_mut(s, a);
+                *q_sa += step * (g - *q_sa);
+            }
+        }
+
+        if let Some(truth) = truth {
+            value_rmse.push(rmse(values.iter().zip(&truth.values).map(|(v, w)| v - w)));
+            q_rmse.push(rmse(
+                (0..num_states)
+                    .flat_map(|s| (0..num_actions).map(move |a| (s, a)))
+                    .map(|(s, a)| q.get(s, a) - truth.q.get(s, a)),
+            ));
+        }
+    }
+
+    McPrediction { values, q, state_visits, value_rmse, q_rmse }
+}
+
+fn rmse(errors: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = errors.fold((0.0, 0), |(sum, count), e| (sum + e * e, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dp::exact_evaluation::exact_policy_evaluation;
+    use crate::core::policies::{DeterministicPolicy, ProbabilisticPolicy};
+    use crate::environments::grid_world::dynamic_programming::grid_world;
+    use crate::environments::line_world::{line_world_dp, LineWorld};
+    use crate::environments::pierre_feuille_ciseaux::PierreFeuilleCiseaux;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_first_and_every_visit_converge_to_exact_values() {
+        let uniform = ProbabilisticPolicy::uniform(5, 2);
+        let truth = exact_policy_evaluation(&line_world_dp(), &uniform, 1.0).unwrap();
+        for visits in [VisitMode::FirstVisit, VisitMode::EveryVisit] {
+            let mut env = LineWorld::new();
+            let prediction = mc_prediction(
+                &mut env,
+                &uniform,
+                20_000,
+                1.0,
+                visits,
+                &Schedule::VisitCount,
+                Some(&truth),
+                &mut StdRng::seed_from_u64(1),
+            );
+            for s in 1..4 {
+                assert!(
+                    (prediction.values[s] - truth.values[s]).abs() < 0.05,
+                    "{:?} : V({}) = {}",
+                    visits,
+                    s,
+                    prediction.values[s]
+                );
+            }
+            assert!((prediction.q.get(2, 1) - truth.q.get(2, 1)).abs() < 0.05);
+            assert_eq!(prediction.value_rmse.len(), 20_000);
+            assert!(prediction.value_rmse.last().unwrap() < &prediction.value_rmse[9]);
+            assert!(prediction.q_rmse.last().unwrap() < &prediction.q_rmse[9]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "nombre d'états")]
+    fn test_truth_for_another_env_is_rejected() {
+        let uniform = ProbabilisticPolicy::uniform(5, 2);
+        // Vérité calculée sur le grid world (25 états), estimation sur le line world (5 états)
+        let grid_uniform = ProbabilisticPolicy::uniform(25, 4);
+        let truth = exact_policy_evaluation(&grid_world(), &grid_uniform, 0.9).unwrap();
+        mc_prediction(
+            &mut LineWorld::new(),
+            &uniform,
+            1,
+            0.9,
+            VisitMode::FirstVisit,
+            &Schedule::VisitCount,
+            Some(&truth),
+            &mut StdRng::seed_from_u64(0),
+        );
+    }
+
+    #[test]
+    fn test_every_visit_counts_revisits() {
+        let uniform = ProbabilisticPolicy::uniform(5, 2);
+        let run = |visits| {
+            mc_prediction(
+                &mut LineWorld::new(),
+                &uniform,
+                500,
+                0.9,
+                visits,
+                &Schedule::VisitCount,
+                None,
+                &mut StdRng::seed_from_u64(7),
+            )
+        };
+        let first = run(VisitMode::FirstVisit);
+        let every = run(VisitMode::EveryVisit);
+        // L'état initial est visité au plus une fois par épisode en first-visit
+        assert_eq!(first.state_visits[2], 500);
+        assert!(every.state_visits[2] > 500);
+        assert!(first.value_rmse.is_empty());
+    }
+
+    #[test]
+    fn test_constant_alpha_tracks_deterministic_return() {
+        // Toujours à droite : retour déterministe γ^(4 - s - 1)
+        let mut env = LineWorld::new();
+        let policy = DeterministicPolicy::from_vec(&env, vec![1; 5]);
+        let prediction = mc_prediction(
+            &mut env,
+            &policy,
+            200,
+            0.9,
+            VisitMode::FirstVisit,
+            &Schedule::Constant(0.1),
+            None,
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert!((prediction.values[2] - 0.9).abs() < 1e-6);
+        assert!((prediction.values[3] - 1.0).abs() < 1e-6);
+        // Les autres états ne sont jamais visités depuis l'état initial
+        assert_eq!(prediction.state_visits[1], 0);
+    }
+
+    #[test]
+    fn test_returns_sum_the_rewards_of_each_step() {
+        // Deux rounds récompensés : avec α = 1 et γ = 1, V(0) vaut le retour du dernier épisode,
+        // soit le score final de la partie
+        let uniform = ProbabilisticPolicy::uniform(13, 3);
+        let mut first_round_rewarded = false;
+        for seed in 0..20 {
+            let mut env = PierreFeuilleCiseaux::new();
+            env.seed(seed);
+            let prediction = mc_prediction(
+                &mut env,
+                &uniform,
+                1,
+                1.0,
+                VisitMode::FirstVisit,
+                &Schedule::Constant(1.0),
+                None,
+                &mut StdRng::seed_from_u64(seed),
+            );
+            assert_eq!(prediction.values[0], env.score());
+            let s1 = (1..4).find(|&s| prediction.state_visits[s] == 1).unwrap();
+            first_round_rewarded |= prediction.values[0] != prediction.values[s1];
+        }
+        assert!(first_round_rewarded);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod dp;
+pub mod exploration;
 pub mod mc;
 pub mod planning;
+pub mod schedule;
 pub mod td;
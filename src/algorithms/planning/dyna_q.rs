@@ -1,111 +1,184 @@
-use crate::algorithms::planning::helpers::{build_policy, choose_action};
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::planning::helpers::build_policy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-use rand::prelude::StdRng;
+use crate::core::q_table::QTable;
 use rand::seq::IteratorRandom;
-use rand::SeedableRng;
+use rand::RngCore;
 use std::collections::HashMap;
 
 type State = usize;
 type Action = usize;
-type QTable = HashMap<(State, Action), f64>;
-type Model = HashMap<(State, Action), (f64, State)>;
+/// (s, a) -> (r, s', s' terminal)
+type Model = HashMap<(State, Action), (f64, State, bool)>;
 
 /// Dyna-Q with tracking of total reward per episode
 pub fn dyna_q(
     env: &mut dyn MonteCarloEnvironment,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
     planning_steps: usize,
     episodes: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    let mut q = QTable::new();
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
     let mut model = Model::new();
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
 
     for ep in 1..=episodes {
         if ep % 100 == 0 {
             println!("=== Épisode {} ===", ep);
         }
+        schedules.begin_episode(ep - 1);
+        exploration.begin_episode(ep - 1);
         // Exécute l'épisode et récupère la récompense totale
         let total_reward = run_episode(
             env,
             &mut q,
             &mut model,
-            alpha,
+            schedules,
             gamma,
-            epsilon,
+            exploration,
             planning_steps,
-            &mut rng,
+            rng,
         );
+        schedules.end_episode();
         rewards_per_episode.push(total_reward);
     }
 
     // Construction de la policy basée sur QTable
-    let states: Vec<usize> = q.keys().map(|&(s, _)| s).collect();
-    let actions: Vec<usize> = q.keys().map(|&(_, a)| a).collect();
-    let policy = build_policy(&q, &states, &actions, env);
-    (policy, rewards_per_episode)
+    let policy = build_policy(&q);
+    (policy, q, rewards_per_episode)
 }
 
-fn run_episode<R: rand::Rng>(
+fn run_episode(
     env: &mut dyn MonteCarloEnvironment,
     q: &mut QTable,
     model: &mut Model,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
     planning_steps: usize,
-    rng: &mut R,
+    rng: &mut dyn RngCore,
 ) -> f64 {
     env.reset();
     let mut total_reward = 0.0;
 
     while !env.is_game_over() {
         let s = env.state_id();
-        let a = choose_action(q, s, &env.available_actions(), epsilon);
+        let a = exploration.select(q, s, &env.available_actions(), rng);
+        schedules.record_epsilon(exploration.epsilon(s));
+        exploration.observe(s, a);
+        schedules.visit(s, a);
 
         env.step(a);
         let s_next = env.state_id();
+        let done = env.is_game_over();
         let reward = env.score();
         total_reward += reward;
 
-        update_q(q, s, a, reward, s_next, gamma, alpha);
-        model.insert((s, a), (reward, s_next));
+        // Le bonus d'exploration n'entre que dans la cible TD, pas dans le modèle
+        let target_reward = reward + exploration.intrinsic_reward(s, a);
+        update_q(q, s, a, target_reward, s_next, done, gamma, schedules.alpha(s, a));
+        model.insert((s, a), (reward, s_next, done));
 
         for _ in 0..planning_steps {
-            planning_step(q, model, gamma, alpha, rng);
+            planning_step(q, model, gamma, schedules, rng);
         }
     }
 
     total_reward
 }
 
-/// Q-update selon TD : Q(s,a) ← Q + α [r + γ max Q(s',·) – Q]
+/// Q-update selon TD : Q(s,a) ← Q + α [r + γ max Q(s',·) – Q], avec max Q(s',·) = 0 si s' est terminal
+#[allow(clippy::too_many_arguments)]
 fn update_q(
     q: &mut QTable,
     s: State,
     a: Action,
     reward: f64,
     s_next: State,
+    done: bool,
     gamma: f64,
     alpha: f64,
 ) {
-    let q_sa = *q.get(&(s, a)).unwrap_or(&0.0);
-    let max_q_next = q
-        .iter()
-        .filter(|&(&(s2, _), _)| s2 == s_next)
-        .map(|(_, &v)| v)
-        .fold(0.0, f64::max);
+    let q_sa = q.get(s, a);
+    let max_q_next = if done { 0.0 } else { q.max_value(s_next) };
 
     let td = reward + gamma * max_q_next - q_sa;
-    q.insert((s, a), q_sa + alpha * td);
+    q.set(s, a, q_sa + alpha * td);
 }
 
 /// Simule une transition passée tirée au hasard du modèle
-fn planning_step<R: rand::Rng>(q: &mut QTable, model: &Model, gamma: f64, alpha: f64, rng: &mut R) {
-    let (&(s, a), &(r, s_next)) = model.iter().choose(rng).unwrap();
-    update_q(q, s, a, r, s_next, gamma, alpha);
+fn planning_step(
+    q: &mut QTable,
+    model: &Model,
+    gamma: f64,
+    schedules: &mut Schedules,
+    rng: &mut dyn RngCore,
+) {
+    let (&(s, a), &(r, s_next, done)) = model.iter().choose(rng).unwrap();
+    update_q(q, s, a, r, s_next, done, gamma, schedules.alpha(s, a));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::algorithms::exploration::{CountBonus, EpsilonGreedy};
+    use crate::core::envs::Environment;
+    use crate::environments::line_world::LineWorld;
+
+    #[test]
+    fn test_dyna_q_returns_its_q_table() {
+        let mut env = LineWorld::new();
+        let (policy, q, rewards) = dyna_q(
+            &mut env,
+            &mut Schedules::constant(0.1),
+            0.9,
+            &mut EpsilonGreedy::new(0.1),
+            5,
+            200,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(rewards.len(), 200);
+        assert_eq!(q.num_states(), env.num_states());
+        assert_eq!(q.num_actions(), env.num_actions());
+        // La policy renvoyée est gloutonne par rapport à la Q-table
+        for s in 1..4 {
+            assert_eq!(policy.get_action(&s), q.best_action(s));
+        }
+        assert!(q.get(3, 1) > 0.5, "Q(3, droite) trop faible: {}", q.get(3, 1));
+    }
+
+    #[test]
+    fn test_count_bonus_stays_out_of_logged_rewards_and_model() {
+        let mut env = LineWorld::new();
+        let mut q = QTable::for_env(&env);
+        let mut model = Model::new();
+        let mut exploration = CountBonus::new(10.0, 0.1);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let total = run_episode(
+                &mut env,
+                &mut q,
+                &mut model,
+                &mut Schedules::constant(0.1),
+                0.9,
+                &mut exploration,
+                5,
+                &mut rng,
+            );
+            // Sur LineWorld, un épisode rapporte exactement -1 ou +1
+            assert!(total == -1.0 || total == 1.0, "récompense journalisée: {}", total);
+        }
+        for &(r, _, _) in model.values() {
+            assert!([-1.0, 0.0, 1.0].contains(&r), "récompense du modèle: {}", r);
+        }
+    }
 }
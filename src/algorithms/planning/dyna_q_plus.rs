@@ -1,62 +1,67 @@
-use crate::algorithms::planning::helpers::{build_policy, choose_action};
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::planning::helpers::build_policy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-use rand::prelude::StdRng;
+use crate::core::q_table::QTable;
 use rand::seq::IteratorRandom;
-use rand::SeedableRng;
+use rand::RngCore;
 use std::collections::HashMap;
 
 type State = usize;
 type Action = usize;
-type QTable = HashMap<(State, Action), f64>;
-type Model = HashMap<(State, Action), (f64, State)>;
+/// (s, a) -> (r, s', s' terminal)
+type Model = HashMap<(State, Action), (f64, State, bool)>;
 type Tau = HashMap<(State, Action), usize>;
 
-/// Dyna-Q+ with tracking of total reward per episode
+/// Dyna-Q+ with tracking of total reward per episode (kappa given by `schedules.kappa`)
 pub fn dyna_q_plus(
     env: &mut dyn MonteCarloEnvironment,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
-    kappa: f64,
+    exploration: &mut dyn ExplorationStrategy,
     planning_steps: usize,
     episodes: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    let all_states = (0..env.num_states()).collect::<Vec<_>>();
-    env.reset();
-    let all_actions = env.available_actions();
-
-    let mut q: QTable = HashMap::new();
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
     let mut model: Model = HashMap::new();
     let mut tau: Tau = HashMap::new();
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
 
     for ep in 1..=episodes {
         if ep % 100 == 0 {
             println!("=== Épisode {} ===", ep);
-        }        env.reset();
+        }
+        schedules.begin_episode(ep - 1);
+        exploration.begin_episode(ep - 1);
+        env.reset();
         let mut total_reward = 0.0;
 
         while !env.is_game_over() {
             let s = env.state_id();
-            let a = choose_action(&q, s, &env.available_actions(), epsilon);
+            let a = exploration.select(&q, s, &env.available_actions(), rng);
+            schedules.record_epsilon(exploration.epsilon(s));
+            exploration.observe(s, a);
+            schedules.visit(s, a);
 
             env.step(a);
             let s_n = env.state_id();
+            let done = env.is_game_over();
             let r = env.score();
             total_reward += r;
+            let bonus = exploration.intrinsic_reward(s, a);
 
             // Initialisation paresseuse
-            q.entry((s, a)).or_insert(0.0);
-            model.entry((s, a)).or_insert((0.0, s));
+            model.entry((s, a)).or_insert((0.0, s, false));
             tau.entry((s, a)).or_insert(0);
 
             // Mise à jour réelle
-            update_q(&mut q, s, a, r, s_n, gamma, alpha);
+            update_q(&mut q, s, a, r + bonus, s_n, done, gamma, schedules.alpha(s, a));
 
-            // Mise à jour du modèle et de tau
-            model.insert((s, a), (r, s_n));
+            // Mise à jour du modèle (récompense extrinsèque seule) et de tau
+            model.insert((s, a), (r, s_n, done));
             tau.insert((s, a), 0);
             for (&sa, t) in tau.iter_mut() {
                 if sa != (s, a) {
@@ -66,47 +71,45 @@ pub fn dyna_q_plus(
 
             // Planning Dyna-Q+ avec bonus
             for _ in 0..planning_steps {
-                planning_step_plus(&mut q, &model, &tau, gamma, alpha, kappa, &mut rng);
+                planning_step_plus(&mut q, &model, &tau, gamma, schedules, rng);
             }
         }
 
+        schedules.end_episode();
         rewards_per_episode.push(total_reward);
     }
 
     // Construction de la policy sur tous les états/actions
-    let policy = build_policy(&q, &all_states, &all_actions, env);
-    (policy, rewards_per_episode)
+    let policy = build_policy(&q);
+    (policy, q, rewards_per_episode)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_q(
     q: &mut QTable,
     s: State,
     a: Action,
     reward: f64,
     s_next: State,
+    done: bool,
     gamma: f64,
     alpha: f64,
 ) {
-    let q_sa = *q.get(&(s, a)).unwrap_or(&0.0);
-    let max_q = q
-        .iter()
-        .filter(|&(&(s2, _), _)| s2 == s_next)
-        .map(|(_, &v)| v)
-        .fold(0.0, f64::max);
+    let q_sa = q.get(s, a);
+    let max_q = if done { 0.0 } else { q.max_value(s_next) };
     let td = reward + gamma * max_q - q_sa;
-    q.insert((s, a), q_sa + alpha * td);
+    q.set(s, a, q_sa + alpha * td);
 }
 
-fn planning_step_plus<R: rand::Rng>(
+fn planning_step_plus(
     q: &mut QTable,
     model: &Model,
     tau: &Tau,
     gamma: f64,
-    alpha: f64,
-    kappa: f64,
-    rng: &mut R,
+    schedules: &mut Schedules,
+    rng: &mut dyn RngCore,
 ) {
-    let (&(s, a), &(r, s_next)) = model.iter().choose(rng).unwrap();
-    let bonus = kappa * (tau[&(s, a)] as f64).sqrt();
-    update_q(q, s, a, r + bonus, s_next, gamma, alpha);
+    let (&(s, a), &(r, s_next, done)) = model.iter().choose(rng).unwrap();
+    let bonus = schedules.kappa(s, a) * (tau[&(s, a)] as f64).sqrt();
+    update_q(q, s, a, r + bonus, s_next, done, gamma, schedules.alpha(s, a));
 }
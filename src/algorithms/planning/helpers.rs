@@ -1,61 +1,70 @@
-use crate::core::envs::MonteCarloEnvironment;
-use crate::core::policies::DeterministicPolicy;
-use rand::prelude::{IndexedRandom, StdRng};
-use rand::{Rng, SeedableRng};
-use std::collections::HashMap;
+use crate::core::policies::{mask_from_available, DeterministicPolicy};
+use crate::core::q_table::QTable;
+use rand::prelude::IndexedRandom;
+use rand::{Rng, RngCore};
 
 type State = usize;
 type Action = usize;
 
-/// Choisit une action selon une stratégie ε‑greedy
+/// Choisit une action selon une stratégie ε‑greedy, en tirant dans `rng`
 pub fn choose_action(
-    q: &HashMap<(State, Action), f64>,
+    q: &QTable,
     s: State,
     actions: &[Action],
     epsilon: f64,
+    rng: &mut dyn RngCore,
 ) -> Action {
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
-
     if rng.random::<f64>() < epsilon {
         // Exploration : choisir une action au hasard
-        *actions.choose(&mut rng).unwrap()
+        *actions.choose(rng).unwrap()
     } else {
         // Exploitation : choisir la meilleure action connue
-        actions
-            .iter()
-            .copied()
-            .max_by(|&a1, &a2| {
-                q.get(&(s, a1))
-                    .unwrap_or(&0.0)
-                    .partial_cmp(q.get(&(s, a2)).unwrap_or(&0.0))
-                    .unwrap()
-            })
+        q.argmax(s, &mask_from_available(q.num_actions(), actions))
             .unwrap_or(actions[0])
     }
 }
 
-/// Construit une politique déterministe à partir d’une Q‑table
-pub fn build_policy(
-    q: &HashMap<(State, Action), f64>,
-    states: &[State],
-    actions: &[Action],
-    env: &dyn MonteCarloEnvironment,
-) -> DeterministicPolicy {
-    let policy_table = states
-        .iter()
-        .map(|&s| {
-            actions
-                .iter()
-                .copied()
-                .max_by(|&a1, &a2| {
-                    q.get(&(s, a1))
-                        .unwrap_or(&0.0)
-                        .partial_cmp(q.get(&(s, a2)).unwrap_or(&0.0))
-                        .unwrap()
-                })
-                .unwrap_or(actions[0])
-        })
-        .collect();
-
-    DeterministicPolicy::from_vec(env, policy_table)
+/// Construit la politique gloutonne d’une Q‑table, sur toutes les actions de chaque état.
+///
+/// Aucun masque n’est appliqué : les actions indisponibles dans l’état courant sont écartées
+/// à l’exécution par `Policy::get_action_masked`. Règle commune aux algorithmes TD et Dyna.
+pub fn build_policy(q: &QTable) -> DeterministicPolicy {
+    q.greedy_policy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::policies::Policy;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_choose_action_explores_every_action() {
+        let mut q = QTable::dense(1, 4);
+        q.set(0, 2, 1.0);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut counts = [0usize; 4];
+        for _ in 0..1000 {
+            counts[choose_action(&q, 0, &[0, 1, 2, 3], 0.5, &mut rng)] += 1;
+        }
+        // ε / 4 ≈ 12.5 % pour chaque action non gloutonne, 62.5 % pour l'action 2
+        assert!(counts.iter().all(|&c| c > 50), "{:?}", counts);
+        assert!(counts[2] > 500, "{:?}", counts);
+
+        // Sans exploration, toujours l'action gloutonne
+        assert!((0..100).all(|_| choose_action(&q, 0, &[0, 1, 2, 3], 0.0, &mut rng) == 2));
+    }
+
+    #[test]
+    fn test_build_policy_leaves_masking_to_execution() {
+        let mut q = QTable::dense(2, 3);
+        q.set(0, 2, 1.0);
+        let policy = build_policy(&q);
+        // Gloutonne sur toutes les actions, même si l'action 2 est indisponible à l'exécution
+        assert_eq!(policy.get_action(&0), 2);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!((0..20).all(|_| policy.get_action_masked(0, &[0, 1], &mut rng) != 2));
+    }
 }
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+type State = usize;
+type Action = usize;
+
+/// Évolution d'un hyperparamètre (alpha, epsilon, kappa) au fil de l'entraînement.
+/// `episode` commence à 0 ; `visits` est le nombre de visites de (s, a) (ou de s pour epsilon).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Schedule {
+    Constant(f64),
+    /// Interpolation de `start` à `end` sur `episodes` épisodes, puis `end`
+    Linear { start: f64, end: f64, episodes: usize },
+    /// start · decay^t, borné inférieurement par `min`
+    Exponential { start: f64, decay: f64, min: f64 },
+    /// start / (1 + decay · t)
+    InverseTime { start: f64, decay: f64 },
+    /// 1 / n(s, a) : moyenne empirique, satisfait les conditions de Robbins–Monro
+    VisitCount,
+    /// Points (épisode, valeur) triés par épisode, interpolés linéairement, constants en dehors
+    Piecewise(Vec<(usize, f64)>),
+}
+
+impl Schedule {
+    pub fn piecewise(points: Vec<(usize, f64)>) -> Self {
+        assert!(!points.is_empty(), "Il faut au moins un point");
+        assert!(
+            points.windows(2).all(|w| w[0].0 < w[1].0),
+            "Les épisodes des points doivent être strictement croissants"
+        );
+        Schedule::Piecewise(points)
+    }
+
+    /// Valeur à l'épisode `episode`, pour un couple visité `visits` fois
+    pub fn value(&self, episode: usize, visits: u64) -> f64 {
+        let t = episode as f64;
+        match self {
+            Schedule::Constant(v) => *v,
+            Schedule::Linear { start, end, episodes } => {
+                let frac = if *episodes == 0 { 1.0 } else { (t / *episodes as f64).min(1.0) };
+                start + (end - start) * frac
+            }
+            Schedule::Exponential { start, decay, min } => (start * decay.powf(t)).max(*min),
+            Schedule::InverseTime { start, decay } => start / (1.0 + decay * t),
+            Schedule::VisitCount => 1.0 / visits.max(1) as f64,
+            Schedule::Piecewise(points) => {
+                let i = points.partition_point(|&(e, _)| e <= episode);
+                if i == 0 {
+                    return points[0].1;
+                }
+                if i == points.len() {
+                    return points[i - 1].1;
+                }
+                let (e0, v0) = points[i - 1];
+                let (e1, v1) = points[i];
+                v0 + (v1 - v0) * (episode - e0) as f64 / (e1 - e0) as f64
+            }
+        }
+    }
+}
+
+impl From<f64> for Schedule {
+    fn from(value: f64) -> Self {
+        Schedule::Constant(value)
+    }
+}
+
+/// Moyenne des valeurs utilisées pendant l'épisode courant
+#[derive(Debug, Default, Clone)]
+struct Trace {
+    sum: f64,
+    count: usize,
+}
+
+impl Trace {
+    fn record(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn take(&mut self) -> Option<f64> {
+        let mean = (self.count > 0).then(|| self.sum / self.count as f64);
+        *self = Trace::default();
+        mean
+    }
+}
+
+/// Valeurs effectives moyennes par épisode (None si le paramètre n'a pas servi)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScheduleLog {
+    pub alpha: Vec<Option<f64>>,
+    pub epsilon: Vec<Option<f64>>,
+    pub kappa: Vec<Option<f64>>,
+}
+
+impl ScheduleLog {
+    pub fn len(&self) -> usize {
+        self.alpha.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alpha.is_empty()
+    }
+
+    pub fn write_csv(&self, path: &str) -> io::Result<()> {
+        let cell = |v: Option<f64>| v.map_or(String::new(), |v| v.to_string());
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "episode,alpha,epsilon,kappa")?;
+        for ep in 0..self.len() {
+            writeln!(
+                file,
+                "{},{},{},{}",
+                ep,
+                cell(self.alpha[ep]),
+                cell(self.epsilon[ep]),
+                cell(self.kappa[ep])
+            )?;
+        }
+        file.flush()
+    }
+}
+
+/// Schedules d'alpha et de kappa d'un entraînement, avec les compteurs de visites
+/// et le journal des valeurs effectives. Le schedule d'epsilon est porté par la
+/// stratégie d'exploration, qui rapporte ses valeurs via `record_epsilon`.
+#[derive(Debug, Clone)]
+pub struct Schedules {
+    pub alpha: Schedule,
+    pub kappa: Schedule,
+    pub log: ScheduleLog,
+    episode: usize,
+    visits: HashMap<(State, Action), u64>,
+    alpha_trace: Trace,
+    epsilon_trace: Trace,
+    kappa_trace: Trace,
+}
+
+impl Schedules {
+    pub fn new(alpha: Schedule) -> Self {
+        Self {
+            alpha,
+            kappa: Schedule::Constant(0.0),
+            log: ScheduleLog::default(),
+            episode: 0,
+            visits: HashMap::new(),
+            alpha_trace: Trace::default(),
+            epsilon_trace: Trace::default(),
+            kappa_trace: Trace::default(),
+        }
+    }
+
+    /// Alpha constant, sans kappa
+    pub fn constant(alpha: f64) -> Self {
+        Self::new(Schedule::Constant(alpha))
+    }
+
+    pub fn with_kappa(mut self, kappa: Schedule) -> Self {
+        self.kappa = kappa;
+        self
+    }
+
+    pub fn begin_episode(&mut self, episode: usize) {
+        self.episode = episode;
+    }
+
+    /// Enregistre une visite réelle de (s, a) (pas les transitions simulées du planning)
+    pub fn visit(&mut self, s: State, a: Action) {
+        *self.visits.entry((s, a)).or_insert(0) += 1;
+    }
+
+    pub fn visits(&self, s: State, a: Action) -> u64 {
+        self.visits.get(&(s, a)).copied().unwrap_or(0)
+    }
+
+    /// Pas d'apprentissage pour une mise à jour de Q(s, a)
+    pub fn alpha(&mut self, s: State, a: Action) -> f64 {
+        let alpha = self.alpha.value(self.episode, self.visits(s, a));
+        self.alpha_trace.record(alpha);
+        alpha
+    }
+
+    /// Coefficient du bonus d'exploration de Dyna-Q+ pour (s, a)
+    pub fn kappa(&mut self, s: State, a: Action) -> f64 {
+        let kappa = self.kappa.value(self.episode, self.visits(s, a));
+        self.kappa_trace.record(kappa);
+        kappa
+    }
+
+    pub fn record_epsilon(&mut self, epsilon: Option<f64>) {
+        if let Some(epsilon) = epsilon {
+            self.epsilon_trace.record(epsilon);
+        }
+    }
+
+    /// Ajoute au journal les moyennes de l'épisode qui se termine
+    pub fn end_episode(&mut self) {
+        self.log.alpha.push(self.alpha_trace.take());
+        self.log.epsilon.push(self.epsilon_trace.take());
+        self.log.kappa.push(self.kappa_trace.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_values() {
+        assert_eq!(Schedule::Constant(0.3).value(1000, 5), 0.3);
+
+        let linear = Schedule::Linear { start: 1.0, end: 0.0, episodes: 10 };
+        assert_eq!(linear.value(0, 0), 1.0);
+        assert!((linear.value(5, 0) - 0.5).abs() < 1e-12);
+        assert_eq!(linear.value(50, 0), 0.0);
+
+        let exp = Schedule::Exponential { start: 1.0, decay: 0.5, min: 0.1 };
+        assert_eq!(exp.value(2, 0), 0.25);
+        assert_eq!(exp.value(10, 0), 0.1);
+
+        let inv = Schedule::InverseTime { start: 1.0, decay: 1.0 };
+        assert_eq!(inv.value(3, 0), 0.25);
+
+        assert_eq!(Schedule::VisitCount.value(0, 0), 1.0);
+        assert_eq!(Schedule::VisitCount.value(0, 4), 0.25);
+
+        let pw = Schedule::piecewise(vec![(10, 1.0), (20, 0.5), (30, 0.1)]);
+        assert_eq!(pw.value(0, 0), 1.0);
+        assert!((pw.value(15, 0) - 0.75).abs() < 1e-12);
+        assert_eq!(pw.value(20, 0), 0.5);
+        assert_eq!(pw.value(100, 0), 0.1);
+    }
+
+    #[test]
+    fn test_schedules_log_effective_values() {
+        let mut schedules = Schedules::new(Schedule::VisitCount);
+        schedules.begin_episode(0);
+        schedules.visit(0, 1);
+        assert_eq!(schedules.alpha(0, 1), 1.0);
+        schedules.visit(0, 1);
+        assert_eq!(schedules.alpha(0, 1), 0.5);
+        schedules.record_epsilon(Some(0.2));
+        schedules.end_episode();
+
+        schedules.begin_episode(1);
+        schedules.record_epsilon(None);
+        schedules.end_episode();
+
+        assert_eq!(schedules.log.alpha, vec![Some(0.75), None]);
+        assert_eq!(schedules.log.epsilon, vec![Some(0.2), None]);
+        assert_eq!(schedules.log.kappa, vec![None, None]);
+    }
+
+    #[test]
+    fn test_q_learning_logs_scheduled_values() {
+        use crate::algorithms::exploration::EpsilonGreedy;
+        use crate::algorithms::td::q_learning::q_learning;
+        use crate::environments::line_world::LineWorld;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut env = LineWorld::new();
+        let mut schedules = Schedules::new(Schedule::InverseTime { start: 0.5, decay: 0.1 });
+        let mut exploration =
+            EpsilonGreedy::scheduled(Schedule::Linear { start: 1.0, end: 0.0, episodes: 50 });
+        let (policy, _, _) = q_learning(
+            &mut env,
+            &mut schedules,
+            0.9,
+            &mut exploration,
+            100,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        let log = &schedules.log;
+        assert_eq!(log.len(), 100);
+        assert_eq!(log.alpha[0], Some(0.5));
+        assert_eq!(log.epsilon[0], Some(1.0));
+        assert!((log.alpha[10].unwrap() - 0.25).abs() < 1e-12);
+        assert_eq!(log.epsilon[60], Some(0.0));
+        assert!(log.kappa.iter().all(Option::is_none));
+        for s in 1..4 {
+            assert_eq!(policy.get_action(&s), 1);
+        }
+    }
+}
@@ -1,47 +1,49 @@
-use crate::algorithms::planning::helpers::{build_policy, choose_action};
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::planning::helpers::build_policy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-
-use std::collections::HashMap;
+use crate::core::q_table::QTable;
+use rand::RngCore;
 
 type State = usize;
 type Action = usize;
-type QTable = HashMap<(State, Action), f64>;
 
 /// Expected SARSA with tracking of total reward per episode
 pub fn expected_sarsa(
     env: &mut dyn MonteCarloEnvironment,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
     episodes: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    // États et actions globaux pour la construction de la policy
-    let all_states = (0..env.num_states()).collect::<Vec<_>>();
-    env.reset();
-    let all_actions = env.available_actions();
-
-    let mut q: QTable = HashMap::new();
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
 
     for ep in 1..=episodes {
         if ep % 100 == 0 {
             println!("=== Épisode {} ===", ep);
         }
-        let total_reward = run_episode(env, &mut q, alpha, gamma, epsilon);
+        schedules.begin_episode(ep - 1);
+        exploration.begin_episode(ep - 1);
+        let total_reward = run_episode(env, &mut q, schedules, gamma, exploration, rng);
+        schedules.end_episode();
         rewards_per_episode.push(total_reward);
     }
 
-    let policy = build_policy(&q, &all_states, &all_actions, env);
-    (policy, rewards_per_episode)
+    let policy = build_policy(&q);
+    (policy, q, rewards_per_episode)
 }
 
 fn run_episode(
     env: &mut dyn MonteCarloEnvironment,
     q: &mut QTable,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
+    rng: &mut dyn RngCore,
 ) -> f64 {
     env.reset();
     let mut total_reward = 0.0;
@@ -49,45 +51,42 @@ fn run_episode(
     while !env.is_game_over() {
         let s = env.state_id();
         let actions = env.available_actions();
-        let a = choose_action(q, s, &actions, epsilon);
+        let a = exploration.select(q, s, &actions, rng);
+        schedules.record_epsilon(exploration.epsilon(s));
+        exploration.observe(s, a);
+        schedules.visit(s, a);
 
         env.step(a);
         let s_next = env.state_id();
         let reward = env.score();
         total_reward += reward;
 
-        let next_actions = env.available_actions();
-        let expected_q = compute_expected_q(q, s_next, &next_actions, epsilon);
+        let expected_q = if env.is_game_over() {
+            0.0
+        } else {
+            compute_expected_q(q, s_next, &env.available_actions(), &*exploration)
+        };
+        let bonus = exploration.intrinsic_reward(s, a);
+        let alpha = schedules.alpha(s, a);
 
-        update_q(q, s, a, reward, expected_q, gamma, alpha);
+        update_q(q, s, a, reward + bonus, expected_q, gamma, alpha);
     }
 
     total_reward
 }
 
-/// Calcule E[Q(s', ·)] sous la politique ε-greedy
-fn compute_expected_q(q: &QTable, s_next: State, actions: &[Action], epsilon: f64) -> f64 {
-    // Choisir l'action greedy (max Q)
-    let greedy = actions
+/// Calcule E[Q(s', ·)] sous la policy de comportement de la stratégie d'exploration
+fn compute_expected_q(
+    q: &QTable,
+    s_next: State,
+    actions: &[Action],
+    exploration: &dyn ExplorationStrategy,
+) -> f64 {
+    let probs = exploration.action_probs(q, s_next, actions);
+    actions
         .iter()
-        .copied()
-        .max_by(|&a1, &a2| {
-            q.get(&(s_next, a1))
-                .unwrap_or(&0.0)
-                .partial_cmp(q.get(&(s_next, a2)).unwrap_or(&0.0))
-                .unwrap()
-        })
-        .unwrap_or(actions[0]);
-
-    let n = actions.len() as f64;
-    actions.iter().copied().fold(0.0, |sum, ap| {
-        let p = if ap == greedy {
-            1.0 - epsilon + (epsilon / n)
-        } else {
-            epsilon / n
-        };
-        sum + p * q.get(&(s_next, ap)).unwrap_or(&0.0)
-    })
+        .map(|&ap| probs[ap] * q.get(s_next, ap))
+        .sum()
 }
 
 /// Mise à jour TD : Q(s, a) += α [r + γ E[Q] − Q(s, a)]
@@ -100,6 +99,6 @@ fn update_q(
     gamma: f64,
     alpha: f64,
 ) {
-    let entry = q.entry((s, a)).or_insert(0.0);
+    let entry = q.get_mut(s, a);
     *entry += alpha * (reward + gamma * expected_q - *entry);
 }
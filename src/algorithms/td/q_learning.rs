@@ -1,47 +1,49 @@
-use crate::algorithms::planning::helpers::{choose_action, build_policy};
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::planning::helpers::build_policy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
-use crate::core::policies::DeterministicPolicy;
-
-use std::collections::HashMap;
+use crate::core::policies::{mask_from_available, DeterministicPolicy};
+use crate::core::q_table::QTable;
+use rand::RngCore;
 
 type State = usize;
 type Action = usize;
-type QTable = HashMap<(State, Action), f64>;
 
 /// Q-Learning with tracking of total reward per episode
 pub fn q_learning(
     env: &mut dyn MonteCarloEnvironment,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
     episodes: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    // États et actions globaux pour la construction de la policy
-    let all_states  = (0..env.num_states()).collect::<Vec<_>>();
-    env.reset();
-    let all_actions = env.available_actions();
-
-    let mut q: QTable = HashMap::new();
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
 
     for ep in 1..=episodes {
         if ep % 100 == 0 {
             println!("=== Épisode {} ===", ep);
         }
-        let total_reward = run_episode(env, &mut q, alpha, gamma, epsilon);
+        schedules.begin_episode(ep - 1);
+        exploration.begin_episode(ep - 1);
+        let total_reward = run_episode(env, &mut q, schedules, gamma, exploration, rng);
+        schedules.end_episode();
         rewards_per_episode.push(total_reward);
     }
 
-    let policy = build_policy(&q, &all_states, &all_actions, env);
-    (policy, rewards_per_episode)
+    let policy = build_policy(&q);
+    (policy, q, rewards_per_episode)
 }
 
 fn run_episode(
     env: &mut dyn MonteCarloEnvironment,
     q: &mut QTable,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
+    rng: &mut dyn RngCore,
 ) -> f64 {
     env.reset();
     let mut total_reward = 0.0;
@@ -49,29 +51,34 @@ fn run_episode(
     while !env.is_game_over() {
         let s        = env.state_id();
         let actions  = env.available_actions();
-        let a        = choose_action(q, s, &actions, epsilon);
+        let a        = exploration.select(q, s, &actions, rng);
+        schedules.record_epsilon(exploration.epsilon(s));
+        exploration.observe(s, a);
+        schedules.visit(s, a);
 
         env.step(a);
         let s_next   = env.state_id();
         let reward   = env.score();
         total_reward += reward;
 
-        let next_actions = env.available_actions();
-        let max_q_next   = compute_max_q(q, s_next, &next_actions);
+        let max_q_next = if env.is_game_over() {
+            0.0
+        } else {
+            compute_max_q(q, s_next, &env.available_actions())
+        };
+        let bonus = exploration.intrinsic_reward(s, a);
+        let alpha = schedules.alpha(s, a);
 
-        apply_q_update(q, s, a, reward, max_q_next, gamma, alpha);
+        apply_q_update(q, s, a, reward + bonus, max_q_next, gamma, alpha);
     }
 
     total_reward
 }
 
-/// max_{a'} Q(s', a')
+/// max_{a'} Q(s', a') sur les actions disponibles (0 s'il n'y en a aucune)
 fn compute_max_q(q: &QTable, s_next: State, actions: &[Action]) -> f64 {
-    actions
-        .iter()
-        .copied()
-        .map(|ap| *q.get(&(s_next, ap)).unwrap_or(&0.0))
-        .fold(0.0, f64::max)
+    q.max(s_next, &mask_from_available(q.num_actions(), actions))
+        .unwrap_or(0.0)
 }
 
 /// Q(s,a) += α [r + γ max_q_next − Q(s,a)]
@@ -84,6 +91,6 @@ fn apply_q_update(
     gamma: f64,
     alpha: f64,
 ) {
-    let entry = q.entry((s, a)).or_insert(0.0);
+    let entry = q.get_mut(s, a);
     *entry += alpha * (reward + gamma * max_q_next - *entry);
 }
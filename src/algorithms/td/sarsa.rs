@@ -1,48 +1,50 @@
-use crate::algorithms::planning::helpers::{build_policy, choose_action};
+use crate::algorithms::exploration::ExplorationStrategy;
+use crate::algorithms::planning::helpers::build_policy;
+use crate::algorithms::schedule::Schedules;
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::DeterministicPolicy;
-
-use std::collections::HashMap;
+use crate::core::q_table::QTable;
+use rand::RngCore;
 
 type State = usize;
 type Action = usize;
-type QTable = HashMap<(State, Action), f64>;
 
 /// SARSA (on-policy TD control) with tracking of total reward per episode
 /// Q(s,a) ← Q + α [r + γ Q(s',a') − Q]
 pub fn sarsa(
     env: &mut dyn MonteCarloEnvironment,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
     episodes: usize,
-) -> (DeterministicPolicy, Vec<f64>) {
-    // États et actions globaux pour la construction de la policy
-    let all_states = (0..env.num_states()).collect::<Vec<_>>();
-    env.reset();
-    let all_actions = (0..env.num_actions()).collect::<Vec<_>>();
-
-    let mut q: QTable = HashMap::new();
+    rng: &mut dyn RngCore,
+) -> (DeterministicPolicy, QTable, Vec<f64>) {
+    let mut q = QTable::for_env(&*env);
+    exploration.initialize(&mut q);
     let mut rewards_per_episode = Vec::with_capacity(episodes);
 
     for ep in 1..=episodes {
         if ep % 100 == 0 {
             println!("=== Épisode {} ===", ep);
         }
-        let total_reward = run_episode(env, &mut q, alpha, gamma, epsilon);
+        schedules.begin_episode(ep - 1);
+        exploration.begin_episode(ep - 1);
+        let total_reward = run_episode(env, &mut q, schedules, gamma, exploration, rng);
+        schedules.end_episode();
         rewards_per_episode.push(total_reward);
     }
 
-    let policy = build_policy(&q, &all_states, &all_actions, env);
-    (policy, rewards_per_episode)
+    let policy = build_policy(&q);
+    (policy, q, rewards_per_episode)
 }
 
 fn run_episode(
     env: &mut dyn MonteCarloEnvironment,
     q: &mut QTable,
-    alpha: f64,
+    schedules: &mut Schedules,
     gamma: f64,
-    epsilon: f64,
+    exploration: &mut dyn ExplorationStrategy,
+    rng: &mut dyn RngCore,
 ) -> f64 {
     env.reset();
     let mut total_reward = 0.0;
@@ -50,13 +52,19 @@ fn run_episode(
     // initial state and action
     let mut s = env.state_id();
     let actions = env.available_actions();
-    let mut a = choose_action(q, s, &actions, epsilon);
+    let mut a = exploration.select(q, s, &actions, rng);
+    schedules.record_epsilon(exploration.epsilon(s));
 
     while !env.is_game_over() {
+        exploration.observe(s, a);
+        schedules.visit(s, a);
         env.step(a);
-        let r = env.score();
-        total_reward += r;
+        let reward = env.score();
+        total_reward += reward;
+        // Le bonus d'exploration n'entre que dans la cible TD
+        let r = reward + exploration.intrinsic_reward(s, a);
         let s_next = env.state_id();
+        let alpha = schedules.alpha(s, a);
 
         if env.is_game_over() {
             update_terminal(q, s, a, r, alpha);
@@ -64,7 +72,8 @@ fn run_episode(
         }
 
         let next_actions = env.available_actions();
-        let a_next = choose_action(q, s_next, &next_actions, epsilon);
+        let a_next = exploration.select(q, s_next, &next_actions, rng);
+        schedules.record_epsilon(exploration.epsilon(s_next));
         update_q(q, s, a, r, s_next, a_next, gamma, alpha);
 
         s = s_next;
@@ -85,15 +94,13 @@ fn update_q(
     gamma: f64,
     alpha: f64,
 ) {
-    let q_sa = *q.get(&(s, a)).unwrap_or(&0.0);
-    let q_next = *q.get(&(s_next, a_next)).unwrap_or(&0.0);
-    let new_q = q_sa + alpha * (reward + gamma * q_next - q_sa);
-    q.insert((s, a), new_q);
+    let q_sa = q.get(s, a);
+    let q_next = q.get(s_next, a_next);
+    q.set(s, a, q_sa + alpha * (reward + gamma * q_next - q_sa));
 }
 
 /// Si s' est terminal, Q(s,a) += α [r − Q(s,a)]
 fn update_terminal(q: &mut QTable, s: State, a: Action, reward: f64, alpha: f64) {
-    let q_sa = *q.get(&(s, a)).unwrap_or(&0.0);
-    let new_q = q_sa + alpha * (reward - q_sa);
-    q.insert((s, a), new_q);
+    let q_sa = q.get(s, a);
+    q.set(s, a, q_sa + alpha * (reward - q_sa));
 }
@@ -0,0 +1,346 @@
+//! Format versionné des résultats d'entraînement (policy, V ou Q), accompagné des
+//! métadonnées qui permettent de savoir d'où il vient et sur quel env le rejouer.
+
+use crate::core::envs::Environment;
+use crate::core::policies::{DeterministicPolicy, NonStationaryPolicy, Policy, ProbabilisticPolicy};
+use crate::core::q_table::QTable;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Version du format, incrémentée à chaque changement incompatible
+pub const ARTIFACT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    /// L'artefact a été produit pour un autre env
+    EnvMismatch { expected: String, found: String },
+    /// Le contenu n'est pas une policy
+    NotAPolicy(&'static str),
+}
+
+impl Display for ArtifactError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArtifactError::Io(e) => write!(f, "Erreur d'E/S : {}", e),
+            ArtifactError::Parse(e) => write!(f, "Artefact illisible : {}", e),
+            ArtifactError::UnsupportedVersion(v) => write!(
+                f,
+                "Version de format {} non supportée (attendue : {})",
+                v, ARTIFACT_FORMAT_VERSION
+            ),
+            ArtifactError::EnvMismatch { expected, found } => write!(
+                f,
+                "Env incompatible : l'artefact attend {}, trouvé {}",
+                expected, found
+            ),
+            ArtifactError::NotAPolicy(kind) => {
+                write!(f, "L'artefact contient {} et non une policy", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+impl From<io::Error> for ArtifactError {
+    fn from(e: io::Error) -> Self {
+        ArtifactError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ArtifactError {
+    fn from(e: serde_json::Error) -> Self {
+        ArtifactError::Parse(e)
+    }
+}
+
+/// Provenance d'un artefact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactMetadata {
+    pub env_name: String,
+    pub num_states: usize,
+    pub num_actions: usize,
+    pub algorithm: String,
+    pub hyperparameters: BTreeMap<String, f64>,
+    pub seed: Option<u64>,
+    pub crate_version: String,
+}
+
+impl ArtifactMetadata {
+    pub fn new(env_name: &str, env: &dyn Environment, algorithm: &str) -> Self {
+        Self {
+            env_name: env_name.to_string(),
+            num_states: env.num_states(),
+            num_actions: env.num_actions(),
+            algorithm: algorithm.to_string(),
+            hyperparameters: BTreeMap::new(),
+            seed: None,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn hyperparameter(mut self, name: &str, value: f64) -> Self {
+        self.hyperparameters.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Contenu d'un artefact
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum Payload {
+    DeterministicPolicy(DeterministicPolicy),
+    ProbabilisticPolicy(ProbabilisticPolicy),
+    /// Policy d'horizon fini, une table par pas de temps
+    NonStationaryPolicy(NonStationaryPolicy),
+    StateValues(Vec<f64>),
+    QValues(QTable),
+}
+
+impl Payload {
+    fn kind(&self) -> &'static str {
+        match self {
+            Payload::DeterministicPolicy(_) => "une policy déterministe",
+            Payload::ProbabilisticPolicy(_) => "une policy probabiliste",
+            Payload::NonStationaryPolicy(_) => "une policy non stationnaire",
+            Payload::StateValues(_) => "des valeurs d'état",
+            Payload::QValues(_) => "des Q-values",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Artifact {
+    pub format_version: u32,
+    pub metadata: ArtifactMetadata,
+    pub payload: Payload,
+}
+
+impl Artifact {
+    pub fn new(metadata: ArtifactMetadata, payload: Payload) -> Self {
+        Self {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            metadata,
+            payload,
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        crate::core::policies::save_to_file(self, path)
+    }
+
+    /// Relit un artefact JSON en refusant les versions de format inconnues
+    pub fn load(path: &str) -> Result<Self, ArtifactError> {
+        let json = std::fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        let version = value
+            .get("format_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as u32;
+        if version != ARTIFACT_FORMAT_VERSION {
+            return Err(ArtifactError::UnsupportedVersion(version));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Vérifie que l'artefact a été produit pour `env` (et pour `env_name` si fourni)
+    pub fn check_env(
+        &self,
+        env: &dyn Environment,
+        env_name: Option<&str>,
+    ) -> Result<(), ArtifactError> {
+        let m = &self.metadata;
+        let name_matches = env_name.is_none_or(|name| name == m.env_name);
+        if !name_matches || m.num_states != env.num_states() || m.num_actions != env.num_actions()
+        {
+            return Err(ArtifactError::EnvMismatch {
+                expected: format!(
+                    "{} ({} états, {} actions)",
+                    m.env_name, m.num_states, m.num_actions
+                ),
+                found: format!(
+                    "{} ({} états, {} actions)",
+                    env_name.unwrap_or("?"),
+                    env.num_states(),
+                    env.num_actions()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn policy(&self) -> Result<&dyn Policy, ArtifactError> {
+        match &self.payload {
+            Payload::DeterministicPolicy(p) => Ok(p),
+            Payload::ProbabilisticPolicy(p) => Ok(p),
+            Payload::NonStationaryPolicy(p) => Ok(p),
+            other => Err(ArtifactError::NotAPolicy(other.kind())),
+        }
+    }
+
+    /// Exporte le contenu en CSV, une ligne par état (ou par couple état/action)
+    pub fn write_csv(&self, path: &str) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        match &self.payload {
+            Payload::DeterministicPolicy(p) => {
+                writeln!(file, "state,action")?;
+                for (s, a) in p.policy_table.iter().enumerate() {
+                    writeln!(file, "{},{}", s, a)?;
+                }
+            }
+            Payload::ProbabilisticPolicy(p) => {
+                writeln!(file, "state,action,probability")?;
+                for s in 0..p.num_states() {
+                    for (a, proba) in p.action_probabilities(s).iter().enumerate() {
+                        writeln!(file, "{},{},{}", s, a, proba)?;
+                    }
+                }
+            }
+            Payload::NonStationaryPolicy(p) => {
+                writeln!(file, "step,state,action")?;
+                for (t, table) in p.tables.iter().enumerate() {
+                    for (s, a) in table.iter().enumerate() {
+                        writeln!(file, "{},{},{}", t, s, a)?;
+                    }
+                }
+            }
+            Payload::StateValues(values) => {
+                writeln!(file, "state,value")?;
+                for (s, v) in values.iter().enumerate() {
+                    writeln!(file, "{},{}", s, v)?;
+                }
+            }
+            Payload::QValues(q) => {
+                writeln!(file, "state,action,q_value")?;
+                for (s, a, v) in q.entries() {
+                    writeln!(file, "{},{},{}", s, a, v)?;
+                }
+            }
+        }
+        file.flush()
+    }
+}
+
+/// Exporte les récompenses par épisode en CSV
+pub fn write_rewards_csv(rewards: &[f64], path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "episode,total_reward")?;
+    for (i, r) in rewards.iter().enumerate() {
+        writeln!(file, "{},{}", i, r)?;
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environments::grid_world::GridWorld;
+    use crate::environments::line_world::LineWorld;
+
+    fn temp_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("artifact_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_round_trip_and_env_validation() {
+        let env = LineWorld::new();
+        let policy = DeterministicPolicy::from_vec(&env, vec![0, 1, 1, 1, 0]);
+        let metadata = ArtifactMetadata::new("line_world", &env, "policy_iteration")
+            .hyperparameter("gamma", 0.99)
+            .seed(7);
+        let artifact = Artifact::new(metadata.clone(), Payload::DeterministicPolicy(policy));
+
+        let path = temp_path("policy.json");
+        artifact.save(&path).unwrap();
+        let loaded = Artifact::load(&path).unwrap();
+        assert_eq!(loaded.metadata, metadata);
+        assert_eq!(loaded.policy().unwrap().get_action(&2), 1);
+
+        assert!(loaded.check_env(&env, Some("line_world")).is_ok());
+        assert!(matches!(
+            loaded.check_env(&GridWorld::new(), None),
+            Err(ArtifactError::EnvMismatch { .. })
+        ));
+        assert!(matches!(
+            loaded.check_env(&env, Some("grid_world")),
+            Err(ArtifactError::EnvMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_non_stationary_policy_round_trip() {
+        let env = LineWorld::new();
+        // Droite au premier pas, gauche ensuite
+        let policy = NonStationaryPolicy::new(5, 2, vec![vec![1; 5], vec![0; 5]]);
+        let artifact = Artifact::new(
+            ArtifactMetadata::new("line_world", &env, "finite_horizon"),
+            Payload::NonStationaryPolicy(policy),
+        );
+
+        let path = temp_path("non_stationary_policy.json");
+        artifact.save(&path).unwrap();
+        let loaded = Artifact::load(&path).unwrap();
+        let policy = loaded.policy().unwrap();
+        assert_eq!(policy.get_action_at(&2, 0), 1);
+        assert_eq!(policy.get_action_at(&2, 1), 0);
+        assert_eq!(policy.get_action_at(&2, 5), 0);
+
+        let csv_path = temp_path("non_stationary_policy.csv");
+        loaded.write_csv(&csv_path).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "step,state,action");
+        assert_eq!(lines.len(), 1 + 2 * 5);
+        assert_eq!(lines[6], "1,0,0");
+    }
+
+    #[test]
+    fn test_unknown_version_and_non_policy_are_rejected() {
+        let env = LineWorld::new();
+        let mut artifact = Artifact::new(
+            ArtifactMetadata::new("line_world", &env, "value_iteration"),
+            Payload::StateValues(vec![0.0, 0.5, 0.7, 0.9, 0.0]),
+        );
+        assert!(matches!(artifact.policy(), Err(ArtifactError::NotAPolicy(_))));
+
+        artifact.format_version = ARTIFACT_FORMAT_VERSION + 1;
+        let path = temp_path("values.json");
+        artifact.save(&path).unwrap();
+        assert!(matches!(
+            Artifact::load(&path),
+            Err(ArtifactError::UnsupportedVersion(v)) if v == ARTIFACT_FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let env = LineWorld::new();
+        let mut q = QTable::for_env(&env);
+        q.set(3, 1, 0.5);
+        let artifact = Artifact::new(
+            ArtifactMetadata::new("line_world", &env, "q_learning"),
+            Payload::QValues(q),
+        );
+        let path = temp_path("q_values.csv");
+        artifact.write_csv(&path).unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "state,action,q_value");
+        assert_eq!(lines.len(), 1 + 5 * 2);
+        assert_eq!(lines[8], "3,1,0.5");
+    }
+}
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 pub trait Environment {
     fn num_states(&self) -> usize;
     fn num_actions(&self) -> usize;
@@ -14,6 +16,8 @@ pub trait MonteCarloEnvironment: Environment {
     }
     fn display(&self);
     fn start_from_random_state(&mut self);
+    /// Réinitialise le RNG propre à l'env (sans effet pour les envs externes, non seedables)
+    fn seed(&mut self, _seed: u64) {}
     fn state_id(&self) -> usize;
     fn is_forbidden(&self, action: usize) -> bool;
     fn action_name(&self, action: usize) -> String {
@@ -39,6 +43,21 @@ pub trait DynamicProgramingEnvironment: Environment {
     );
     fn get_reward(&self, i: usize) -> f64;
     fn get_terminal_states(&self) -> Vec<usize>;
+
+    /// Transitions de probabilité non nulle depuis (state, action), sous la forme
+    /// (state_prime, reward_index, proba). Par défaut parcourt tous les (state_prime, reward_index).
+    fn transitions_from(&self, state: usize, action: usize) -> Vec<(usize, usize, f64)> {
+        let mut transitions = Vec::new();
+        for state_prime in 0..self.num_states() {
+            for reward_index in 0..self.num_rewards() {
+                let p = self.get_transition_prob(state, action, state_prime, reward_index);
+                if p != 0.0 {
+                    transitions.push((state_prime, reward_index, p));
+                }
+            }
+        }
+        transitions
+    }
 }
 
 /// Permet de représenter un environnement pour l'utiliser avec les fontions de dynamic programming
@@ -155,4 +174,205 @@ impl DynamicProgramingEnvironment for DPEnvironment {
     fn get_terminal_states(&self) -> Vec<usize> {
         self.terminal_states.clone()
     }
+
+    fn transitions_from(&self, state: usize, action: usize) -> Vec<(usize, usize, f64)> {
+        let start = self.get_index(state, action, 0, 0);
+        self.transitions[start..start + self.num_states * self.num_rewards]
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p != 0.0)
+            .map(|(i, &p)| (i / self.num_rewards, i % self.num_rewards, p))
+            .collect()
+    }
+}
+
+/// Représentation creuse d'un environnement DP : seules les transitions non nulles sont stockées.
+/// Adaptée aux modèles de plusieurs milliers d'états où `DPEnvironment` serait trop gros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseDPEnvironment {
+    pub num_states: usize,
+    pub num_actions: usize,
+    pub num_rewards: usize,
+    pub rewards: Vec<f64>,
+    pub terminal_states: Vec<usize>,
+
+    // transitions[state * num_actions + action] = [(state_prime, reward_index, proba)]
+    pub transitions: Vec<Vec<(usize, usize, f64)>>,
+}
+
+impl SparseDPEnvironment {
+    pub fn new(
+        num_states: usize,
+        num_actions: usize,
+        num_rewards: usize,
+        rewards: Vec<f64>,
+        terminal_states: Vec<usize>,
+    ) -> Self {
+        SparseDPEnvironment {
+            num_states,
+            num_actions,
+            num_rewards,
+            rewards,
+            terminal_states,
+            transitions: vec![Vec::new(); num_states * num_actions],
+        }
+    }
+
+    /// Copie le modèle de `env` en un seul parcours de ses probabilités de transition.
+    /// Les états terminaux sont ceux déclarés par `env` complétés par ceux déduits de la structure.
+    pub fn from_env(env: &dyn DynamicProgramingEnvironment) -> Self {
+        let rewards = (0..env.num_rewards()).map(|i| env.get_reward(i)).collect();
+        let mut sparse = SparseDPEnvironment::new(
+            env.num_states(),
+            env.num_actions(),
+            env.num_rewards(),
+            rewards,
+            Vec::new(),
+        );
+
+        for s in 0..env.num_states() {
+            for a in 0..env.num_actions() {
+                sparse.transitions[s * env.num_actions() + a] = env.transitions_from(s, a);
+            }
+        }
+
+        let mut terminal_states = env.get_terminal_states();
+        terminal_states.extend(sparse.infer_terminal_states());
+        terminal_states.sort();
+        terminal_states.dedup();
+        sparse.terminal_states = terminal_states;
+
+        sparse
+    }
+
+    /// Déduit les états terminaux de la structure des transitions : un état est terminal si,
+    /// pour toute action, il n'a aucune transition sortante ou ne fait que boucler sur lui-même
+    /// avec une reward nulle (état absorbant).
+    pub fn infer_terminal_states(&self) -> Vec<usize> {
+        (0..self.num_states)
+            .filter(|&s| {
+                (0..self.num_actions).all(|a| {
+                    self.transitions[s * self.num_actions + a]
+                        .iter()
+                        .all(|&(s_prime, r_index, _)| s_prime == s && self.rewards[r_index] == 0.0)
+                })
+            })
+            .collect()
+    }
+
+    /// Nombre de transitions non nulles stockées
+    pub fn num_transitions(&self) -> usize {
+        self.transitions.iter().map(|t| t.len()).sum()
+    }
+
+    fn check_index(&self, state: usize, action: usize, state_prime: usize, reward_index: usize) {
+        assert!(state < self.num_states, "state index out of bounds");
+        assert!(action < self.num_actions, "action index out of bounds");
+        assert!(
+            state_prime < self.num_states,
+            "state_prime index out of bounds"
+        );
+        assert!(
+            reward_index < self.num_rewards,
+            "reward_index index out of bounds"
+        );
+    }
+}
+
+impl Environment for SparseDPEnvironment {
+    fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    fn num_rewards(&self) -> usize {
+        self.num_rewards
+    }
+}
+
+impl DynamicProgramingEnvironment for SparseDPEnvironment {
+    fn get_transition_prob(
+        &self,
+        state: usize,
+        action: usize,
+        state_prime: usize,
+        reward_index: usize,
+    ) -> f64 {
+        self.check_index(state, action, state_prime, reward_index);
+        self.transitions[state * self.num_actions + action]
+            .iter()
+            .find(|&&(sp, r, _)| sp == state_prime && r == reward_index)
+            .map_or(0.0, |&(_, _, p)| p)
+    }
+
+    fn set_transition_prob(
+        &mut self,
+        state: usize,
+        action: usize,
+        state_prime: usize,
+        reward_index: usize,
+        value: f64,
+    ) {
+        self.check_index(state, action, state_prime, reward_index);
+        let row = &mut self.transitions[state * self.num_actions + action];
+        row.retain(|&(sp, r, _)| sp != state_prime || r != reward_index);
+        if value != 0.0 {
+            row.push((state_prime, reward_index, value));
+        }
+    }
+
+    fn get_reward(&self, i: usize) -> f64 {
+        self.rewards[i]
+    }
+
+    fn get_terminal_states(&self) -> Vec<usize> {
+        self.terminal_states.clone()
+    }
+
+    fn transitions_from(&self, state: usize, action: usize) -> Vec<(usize, usize, f64)> {
+        self.transitions[state * self.num_actions + action].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environments::line_world::line_world_dp;
+
+    #[test]
+    fn test_sparse_from_env_matches_dense_model() {
+        let dense = line_world_dp();
+        let sparse = SparseDPEnvironment::from_env(&dense);
+
+        assert_eq!(sparse.num_transitions(), 6);
+        for s in 0..dense.num_states {
+            for a in 0..dense.num_actions {
+                for s_prime in 0..dense.num_states {
+                    for r in 0..dense.num_rewards {
+                        assert_eq!(
+                            sparse.get_transition_prob(s, a, s_prime, r),
+                            dense.get_transition_prob(s, a, s_prime, r)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_infer_terminal_states() {
+        let mut dense = line_world_dp();
+        dense.terminal_states.clear();
+        let sparse = SparseDPEnvironment::from_env(&dense);
+        assert_eq!(sparse.infer_terminal_states(), vec![0, 4]);
+        assert_eq!(sparse.get_terminal_states(), vec![0, 4]);
+
+        // Un état qui boucle sur lui-même avec une reward non nulle n'est pas absorbant
+        let mut looping = sparse.clone();
+        looping.set_transition_prob(4, 0, 4, 2, 1.0);
+        assert_eq!(looping.infer_terminal_states(), vec![0]);
+    }
 }
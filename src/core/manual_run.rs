@@ -1,3 +1,4 @@
+use crate::core::artifact::{Artifact, ArtifactError};
 use crate::core::envs::MonteCarloEnvironment;
 use crate::core::policies::Policy;
 use std::io;
@@ -8,14 +9,21 @@ pub fn run_policy(env: &mut dyn MonteCarloEnvironment, policy: &dyn Policy) {
 
     env.display();
 
+    // Pas de l'épisode, pour les policies non stationnaires (qui s'arrêtent à leur horizon)
+    let mut t = 0;
     while !env.is_game_over() {
+        if policy.step_limit().is_some_and(|limit| t >= limit) {
+            println!("Horizon de la policy atteint");
+            break;
+        }
         println!("Appuyer sur Entrée pour continuer...");
         io::stdout().flush().unwrap();
         let mut buf = String::new();
         io::stdin().read_line(&mut buf).unwrap();
 
         let state_id = env.state_id();
-        let action = policy.get_action(&state_id);
+        let action = policy.get_action_at(&state_id, t);
+        t += 1;
         println!("Action choisie: {}", action);
 
         env.step(action);
@@ -28,6 +36,19 @@ pub fn run_policy(env: &mut dyn MonteCarloEnvironment, policy: &dyn Policy) {
     println!("Fin du jeu");
 }
 
+/// Charge un artefact de policy et le rejoue pas à pas, après avoir vérifié qu'il a été
+/// produit pour `env_name` et pour un env de mêmes dimensions
+pub fn run_saved_policy(
+    env: &mut dyn MonteCarloEnvironment,
+    env_name: &str,
+    path: &str,
+) -> Result<(), ArtifactError> {
+    let artifact = Artifact::load(path)?;
+    artifact.check_env(&*env, Some(env_name))?;
+    run_policy(env, artifact.policy()?);
+    Ok(())
+}
+
 /// Permet à l'utilisateur de choisir les actions manuellement dans un environnement Monte Carlo
 pub fn run_manual(env: &mut dyn MonteCarloEnvironment) {
     env.reset();
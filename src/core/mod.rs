@@ -1,4 +1,7 @@
+pub mod artifact;
 pub mod manual_run;
 pub mod envs;
 pub mod policies;
+pub mod q_table;
+pub mod rng;
 pub mod utils;
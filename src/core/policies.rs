@@ -1,15 +1,103 @@
-use crate::core::envs::{Environment, MonteCarloEnvironment};
-use rand;
-use rand::{random, SeedableRng};
+use crate::core::envs::Environment;
+use crate::core::rng::{default_rng, POLICY_STREAM};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::fmt::{Display, Formatter};
 use std::{fs, io};
-use rand::prelude::{IndexedRandom, StdRng};
 
 /// Interface générale pour les policies
 pub trait Policy {
     fn get_action(&self, state: &usize) -> usize;
+
+    fn num_actions(&self) -> usize;
+
+    /// Distribution π(·|s) restreinte aux actions où `mask[a]` est vrai, puis renormalisée.
+    /// Si la policy ne donne aucune masse aux actions autorisées, elle est uniforme sur celles-ci.
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64>;
+
+    /// Tire une action parmi `available` selon `action_probs`
+    fn get_action_masked(&self, state: usize, available: &[usize], rng: &mut dyn RngCore) -> usize {
+        self.get_action_masked_at(state, 0, available, rng)
+    }
+
+    /// Action au pas `t` de l'épisode ; les policies stationnaires ignorent `t`
+    fn get_action_at(&self, state: &usize, _t: usize) -> usize {
+        self.get_action(state)
+    }
+
+    /// Nombre de pas pour lesquels la policy est définie (horizon fini) : un épisode qui la suit
+    /// s'arrête après ce nombre de pas. `None` pour les policies stationnaires.
+    fn step_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Distribution π_t(·|s) au pas `t` de l'épisode
+    fn action_probs_at(&self, state: usize, _t: usize, mask: &[bool]) -> Vec<f64> {
+        self.action_probs(state, mask)
+    }
+
+    /// Tire une action parmi `available` selon `action_probs_at`
+    fn get_action_masked_at(
+        &self,
+        state: usize,
+        t: usize,
+        available: &[usize],
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        assert!(!available.is_empty(), "Aucune action disponible dans l'état {}", state);
+        let mask = mask_from_available(self.num_actions(), available);
+        sample_index(&self.action_probs_at(state, t, &mask), rng)
+    }
+}
+
+/// Masque `num_actions` booléens à partir d'une liste d'actions disponibles
+pub fn mask_from_available(num_actions: usize, available: &[usize]) -> Vec<bool> {
+    let mut mask = vec![false; num_actions];
+    for &a in available {
+        mask[a] = true;
+    }
+    mask
+}
+
+/// Met à zéro les actions masquées et renormalise (uniforme sur le masque si la masse est nulle)
+pub fn restrict_to_mask(mut probs: Vec<f64>, mask: &[bool]) -> Vec<f64> {
+    assert_eq!(probs.len(), mask.len(), "Le masque doit couvrir toutes les actions");
+    for (p, &allowed) in probs.iter_mut().zip(mask) {
+        if !allowed {
+            *p = 0.0;
+        }
+    }
+    let sum: f64 = probs.iter().sum();
+    let allowed = mask.iter().filter(|&&m| m).count();
+    for (p, &m) in probs.iter_mut().zip(mask) {
+        if sum > 0.0 {
+            *p /= sum;
+        } else if m {
+            *p = 1.0 / allowed as f64;
+        }
+    }
+    probs
+}
+
+/// Tire un indice selon une distribution (dont la somme vaut 1 aux arrondis près)
+fn sample_index<R: Rng + ?Sized>(probs: &[f64], rng: &mut R) -> usize {
+    let sum: f64 = probs.iter().sum();
+    let mut rand_num = rng.random::<f64>() * sum;
+    for (i, prob) in probs.iter().enumerate() {
+        rand_num -= prob;
+        if rand_num < 0.0 {
+            return i;
+        }
+    }
+
+    // Erreurs d'arrondi : dernière action de probabilité non nulle
+    probs
+        .iter()
+        .rposition(|&p| p > 0.0)
+        .expect("Aucune action de probabilité non nulle")
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +105,13 @@ pub struct ProbabilisticPolicy {
     pub policy_table: Vec<f64>,
     num_states: usize,
     num_actions: usize,
+    /// RNG de `get_action`, réinitialisé par `seed` (en boîte : l'état de `StdRng` est gros)
+    #[serde(skip, default = "default_policy_rng")]
+    rng: RefCell<Box<StdRng>>,
+}
+
+fn default_policy_rng() -> RefCell<Box<StdRng>> {
+    RefCell::new(Box::new(default_rng(POLICY_STREAM)))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,9 +128,10 @@ impl Display for DeterministicPolicy {
 }
 
 impl DeterministicPolicy {
-    pub fn new_det_pol(env: &dyn Environment) -> Self {
+    /// Policy initiale jouant partout la même action, tirée avec `rng`
+    pub fn new_det_pol<R: Rng + ?Sized>(env: &dyn Environment, rng: &mut R) -> Self {
         Self {
-            policy_table: vec![rand::random_range(0..env.num_actions()); env.num_states()],
+            policy_table: vec![rng.random_range(0..env.num_actions()); env.num_states()],
             num_states: env.num_states(),
             num_actions: env.num_actions(),
         }
@@ -49,6 +145,15 @@ impl DeterministicPolicy {
         }
     }
 
+    pub fn from_table(num_states: usize, num_actions: usize, table: Vec<usize>) -> Self {
+        assert_eq!(table.len(), num_states, "La table doit contenir une action par état");
+        Self {
+            policy_table: table,
+            num_states,
+            num_actions,
+        }
+    }
+
     pub fn set_action(&mut self, state: &usize, action: usize) {
         self.policy_table[*state] = action;
     }
@@ -62,47 +167,302 @@ impl Policy for DeterministicPolicy {
     fn get_action(&self, state: &usize) -> usize {
         self.policy_table[*state]
     }
+
+    fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64> {
+        let mut probs = vec![0.0; self.num_actions];
+        probs[self.policy_table[state]] = 1.0;
+        restrict_to_mask(probs, mask)
+    }
+}
+
+/// Policy déterministe dépendant du temps : `tables[t][s]` = π_t(s), pour un horizon fini.
+/// Au-delà du dernier pas, la dernière table est réutilisée.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NonStationaryPolicy {
+    pub tables: Vec<Vec<usize>>,
+    num_states: usize,
+    num_actions: usize,
+}
+
+impl NonStationaryPolicy {
+    pub fn new(num_states: usize, num_actions: usize, tables: Vec<Vec<usize>>) -> Self {
+        assert!(!tables.is_empty(), "Il faut au moins un pas de temps");
+        assert!(
+            tables.iter().all(|table| table.len() == num_states),
+            "Chaque table doit contenir une action par état"
+        );
+        Self {
+            tables,
+            num_states,
+            num_actions,
+        }
+    }
+
+    pub fn horizon(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Policy stationnaire jouée au pas `t`
+    pub fn at_step(&self, t: usize) -> DeterministicPolicy {
+        let table = self.tables[t.min(self.tables.len() - 1)].clone();
+        DeterministicPolicy::from_table(self.num_states, self.num_actions, table)
+    }
+}
+
+impl Policy for NonStationaryPolicy {
+    /// Action du premier pas ; `get_action_at` pour les pas suivants
+    fn get_action(&self, state: &usize) -> usize {
+        self.get_action_at(state, 0)
+    }
+
+    fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64> {
+        self.action_probs_at(state, 0, mask)
+    }
+
+    fn get_action_at(&self, state: &usize, t: usize) -> usize {
+        self.tables[t.min(self.tables.len() - 1)][*state]
+    }
+
+    fn step_limit(&self) -> Option<usize> {
+        Some(self.horizon())
+    }
+
+    fn action_probs_at(&self, state: usize, t: usize, mask: &[bool]) -> Vec<f64> {
+        let mut probs = vec![0.0; self.num_actions];
+        probs[self.get_action_at(&state, t)] = 1.0;
+        restrict_to_mask(probs, mask)
+    }
 }
 
 impl ProbabilisticPolicy {
     pub fn new_pb_pol<E: Environment>(env: &E) -> Self {
+        Self::uniform(env.num_states(), env.num_actions())
+    }
+
+    /// Policy uniforme sur toutes les actions
+    pub fn uniform(num_states: usize, num_actions: usize) -> Self {
         Self {
-            policy_table: vec![
-                1.0 / env.num_actions() as f64;
-                env.num_states() * env.num_actions()
-            ],
-            num_states: env.num_states(),
-            num_actions: env.num_actions(),
+            policy_table: vec![1.0 / num_actions as f64; num_states * num_actions],
+            num_states,
+            num_actions,
+            rng: default_policy_rng(),
         }
     }
 
-    fn set_proba(&mut self, state: usize, action: usize, proba: f64) {
+    /// Construit une policy à partir d'une table `num_states * num_actions`, normalisée par état
+    pub fn from_table(num_states: usize, num_actions: usize, table: Vec<f64>) -> Self {
+        assert_eq!(
+            table.len(),
+            num_states * num_actions,
+            "La table doit contenir num_states * num_actions probabilités"
+        );
+        let mut policy = Self {
+            policy_table: table,
+            num_states,
+            num_actions,
+            rng: default_policy_rng(),
+        };
+        policy.normalize();
+        policy
+    }
+
+    /// Policy ε-greedy par rapport à `q_values[s][a]` : chaque action reçoit ε / |A|
+    /// et l'action gloutonne (la première en cas d'égalité) reçoit en plus 1 - ε
+    pub fn epsilon_greedy(q_values: &[Vec<f64>], epsilon: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&epsilon),
+            "epsilon doit être dans [0, 1]"
+        );
+        let num_actions = q_values.first().map_or(0, |q| q.len());
+        let mut policy = Self::uniform(q_values.len(), num_actions);
+        for (s, q) in q_values.iter().enumerate() {
+            let greedy = argmax(q);
+            for a in 0..num_actions {
+                let base = epsilon / num_actions as f64;
+                let proba = if a == greedy { base + 1.0 - epsilon } else { base };
+                policy.set_proba(s, a, proba);
+            }
+        }
+        policy
+    }
+
+    /// Policy softmax (Boltzmann) sur `q_values[s][a]` avec la température donnée
+    pub fn softmax(q_values: &[Vec<f64>], temperature: f64) -> Self {
+        assert!(temperature > 0.0, "La température doit être strictement positive");
+        let num_actions = q_values.first().map_or(0, |q| q.len());
+        let mut policy = Self::uniform(q_values.len(), num_actions);
+        for (s, q) in q_values.iter().enumerate() {
+            // On soustrait le max pour éviter les débordements de exp
+            let max = q.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            for (a, &value) in q.iter().enumerate() {
+                policy.set_proba(s, a, ((value - max) / temperature).exp());
+            }
+            policy.normalize_state(s);
+        }
+        policy
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    pub fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    pub fn set_proba(&mut self, state: usize, action: usize, proba: f64) {
+        assert!(proba >= 0.0, "Une probabilité ne peut pas être négative");
         self.policy_table[state * self.num_actions + action] = proba;
     }
 
-    fn get_proba(&self, state: usize, action: usize) -> f64 {
+    pub fn get_proba(&self, state: usize, action: usize) -> f64 {
         self.policy_table[state * self.num_actions + action]
     }
+
+    /// Probabilités π(·|s) de chaque action dans l'état donné
+    pub fn action_probabilities(&self, state: usize) -> &[f64] {
+        &self.policy_table[state * self.num_actions..(state + 1) * self.num_actions]
+    }
+
+    /// Remplace la distribution d'un état, puis la normalise
+    pub fn set_action_probabilities(&mut self, state: usize, probas: &[f64]) {
+        assert_eq!(probas.len(), self.num_actions);
+        for (action, &proba) in probas.iter().enumerate() {
+            self.set_proba(state, action, proba);
+        }
+        self.normalize_state(state);
+    }
+
+    /// Ramène la somme des probabilités d'un état à 1 (uniforme si elle est nulle)
+    pub fn normalize_state(&mut self, state: usize) {
+        let range = state * self.num_actions..(state + 1) * self.num_actions;
+        let sum: f64 = self.policy_table[range.clone()].iter().sum();
+        for proba in &mut self.policy_table[range] {
+            *proba = if sum > 0.0 {
+                *proba / sum
+            } else {
+                1.0 / self.num_actions as f64
+            };
+        }
+    }
+
+    pub fn normalize(&mut self) {
+        for state in 0..self.num_states {
+            self.normalize_state(state);
+        }
+    }
+
+    /// Réinitialise le RNG de `get_action`
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = RefCell::new(Box::new(StdRng::seed_from_u64(seed)));
+    }
+
+    /// Tire une action selon π(·|s) avec le générateur fourni
+    pub fn sample_action<R: Rng + ?Sized>(&self, state: usize, rng: &mut R) -> usize {
+        let probs = self.action_probabilities(state);
+        let sum: f64 = probs.iter().sum();
+        assert!(
+            (sum - 1.0).abs() < PROBA_TOLERANCE,
+            "Les probabilités de l'état {} somment à {} au lieu de 1",
+            state,
+            sum
+        );
+        sample_index(probs, rng)
+    }
+}
+
+/// Tolérance sur la somme des probabilités d'un état
+const PROBA_TOLERANCE: f64 = 1e-6;
+
+fn argmax(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate() {
+        if v > values[best] {
+            best = i;
+        }
+    }
+    best
 }
 
 impl Policy for ProbabilisticPolicy {
+    /// Tirage sur le RNG de la policy ; `sample_action` pour un RNG explicite
     fn get_action(&self, state: &usize) -> usize {
-        let mut probs: Vec<f64> = vec![0.0; self.num_actions];
-        for action in 0..self.num_actions {
-            probs.insert(action, self.get_proba(*state, action));
-        }
+        self.sample_action(*state, &mut **self.rng.borrow_mut())
+    }
 
-        assert_eq!(probs.iter().sum::<f64>(), 1.0);
+    fn num_actions(&self) -> usize {
+        self.num_actions
+    }
 
-        let mut rand_num: f64 = random();
-        for (i, prob) in probs.iter().enumerate() {
-            rand_num -= prob;
-            if rand_num <= 0.0 {
-                return i;
-            }
-        }
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64> {
+        restrict_to_mask(self.action_probabilities(state).to_vec(), mask)
+    }
+}
+
+/// Policy ε-greedy construite au-dessus d'une autre policy : avec probabilité ε, une action
+/// autorisée uniforme, sinon l'action de `base`. Sert de policy de comportement.
+pub struct EpsilonGreedyPolicy<'a, P: Policy + ?Sized> {
+    base: &'a P,
+    epsilon: f64,
+    /// RNG de `get_action`, réinitialisé par `seed`
+    rng: RefCell<Box<StdRng>>,
+}
 
-        panic!("Error in get_action");
+impl<'a, P: Policy + ?Sized> EpsilonGreedyPolicy<'a, P> {
+    pub fn new(base: &'a P, epsilon: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&epsilon),
+            "epsilon doit être dans [0, 1]"
+        );
+        Self { base, epsilon, rng: default_policy_rng() }
+    }
+
+    /// Réinitialise le RNG de `get_action`
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = RefCell::new(Box::new(StdRng::seed_from_u64(seed)));
+    }
+}
+
+impl<P: Policy + ?Sized> Policy for EpsilonGreedyPolicy<'_, P> {
+    /// Tirage sur le RNG de la policy ; `get_action_masked` pour un RNG explicite
+    fn get_action(&self, state: &usize) -> usize {
+        let mask = vec![true; self.num_actions()];
+        sample_index(&self.action_probs(*state, &mask), &mut **self.rng.borrow_mut())
+    }
+
+    fn num_actions(&self) -> usize {
+        self.base.num_actions()
+    }
+
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64> {
+        self.action_probs_at(state, 0, mask)
+    }
+
+    fn step_limit(&self) -> Option<usize> {
+        self.base.step_limit()
+    }
+
+    fn action_probs_at(&self, state: usize, t: usize, mask: &[bool]) -> Vec<f64> {
+        let base = self.base.action_probs_at(state, t, mask);
+        let allowed = mask.iter().filter(|&&m| m).count();
+        base.iter()
+            .zip(mask)
+            .map(|(&p, &m)| {
+                if m {
+                    (1.0 - self.epsilon) * p + self.epsilon / allowed as f64
+                } else {
+                    0.0
+                }
+            })
+            .collect()
     }
 }
 
@@ -117,3 +477,129 @@ pub fn load_from_file<T: DeserializeOwned>(path: &str) -> io::Result<T> {
     let obj = serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
     Ok(obj)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_probabilistic_policy_samples_valid_actions() {
+        let policy = ProbabilisticPolicy::uniform(2, 3);
+        let mut counts = [0; 3];
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..3000 {
+            let action = policy.sample_action(1, &mut rng);
+            counts[action] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 800), "{:?}", counts);
+        assert!(policy.get_action(&0) < 3);
+    }
+
+    #[test]
+    fn test_seeded_sampling_is_reproducible() {
+        let policy = ProbabilisticPolicy::softmax(&[vec![0.0, 1.0, 2.0]], 1.0);
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..50)
+                .map(|_| policy.sample_action(0, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(7), draw(7));
+    }
+
+    #[test]
+    fn test_get_action_uses_the_policy_rng() {
+        let draw = |seed: Option<u64>| {
+            let mut policy = ProbabilisticPolicy::uniform(1, 4);
+            if let Some(seed) = seed {
+                policy.seed(seed);
+            }
+            let greedy = DeterministicPolicy::from_table(1, 4, vec![2]);
+            let mut behavior = EpsilonGreedyPolicy::new(&greedy, 0.5);
+            if let Some(seed) = seed {
+                behavior.seed(seed);
+            }
+            (0..50)
+                .map(|_| (policy.get_action(&0), behavior.get_action(&0)))
+                .collect::<Vec<_>>()
+        };
+        // Sans graine, le RNG est dérivé de DEFAULT_SEED : même tirage d'une exécution à l'autre
+        assert_eq!(draw(None), draw(None));
+        assert_eq!(draw(Some(3)), draw(Some(3)));
+        assert_ne!(draw(Some(3)), draw(Some(4)));
+    }
+
+    #[test]
+    fn test_normalization_tolerates_rounding() {
+        let mut policy = ProbabilisticPolicy::uniform(1, 10);
+        // 10 * 0.1 ne vaut pas exactement 1.0 en flottant
+        policy.set_action_probabilities(0, &[0.1; 10]);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            assert!(policy.sample_action(0, &mut rng) < 10);
+        }
+
+        policy.set_action_probabilities(0, &[0.0; 10]);
+        assert!(policy.action_probabilities(0).iter().all(|&p| p == 0.1));
+
+        let policy = ProbabilisticPolicy::from_table(2, 2, vec![2.0, 6.0, 0.0, 0.0]);
+        assert_eq!(policy.action_probabilities(0), &[0.25, 0.75]);
+        assert_eq!(policy.action_probabilities(1), &[0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_masked_distributions() {
+        let mut det = DeterministicPolicy {
+            policy_table: vec![0, 2],
+            num_states: 2,
+            num_actions: 3,
+        };
+        assert_eq!(det.action_probs(1, &[true, true, true]), vec![0.0, 0.0, 1.0]);
+        // Action interdite : uniforme sur les actions autorisées
+        assert_eq!(det.action_probs(1, &[true, true, false]), vec![0.5, 0.5, 0.0]);
+        det.set_action(&1, 1);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50 {
+            assert_eq!(det.get_action_masked(1, &[1, 2], &mut rng), 1);
+            assert_ne!(det.get_action_masked(0, &[1, 2], &mut rng), 0);
+        }
+
+        let pb = ProbabilisticPolicy::from_table(1, 3, vec![0.5, 0.25, 0.25]);
+        assert_eq!(pb.action_probs(0, &[false, true, true]), vec![0.0, 0.5, 0.5]);
+        for _ in 0..50 {
+            assert_ne!(pb.get_action_masked(0, &[1, 2], &mut rng), 0);
+        }
+
+        let behavior = EpsilonGreedyPolicy::new(&det, 0.2);
+        let probs = behavior.action_probs(1, &[true, true, false]);
+        assert!((probs[0] - 0.1).abs() < 1e-12);
+        assert!((probs[1] - 0.9).abs() < 1e-12);
+        assert_eq!(probs[2], 0.0);
+    }
+
+    #[test]
+    fn test_policies_from_q_values() {
+        let q = vec![vec![1.0, 3.0, 2.0], vec![0.0, 0.0, 0.0]];
+
+        let greedy = ProbabilisticPolicy::epsilon_greedy(&q, 0.0);
+        assert_eq!(greedy.action_probabilities(0), &[0.0, 1.0, 0.0]);
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(greedy.sample_action(0, &mut rng), 1);
+
+        let eps = ProbabilisticPolicy::epsilon_greedy(&q, 0.3);
+        assert!((eps.get_proba(0, 1) - 0.8).abs() < 1e-12);
+        assert!((eps.get_proba(0, 0) - 0.1).abs() < 1e-12);
+
+        let soft = ProbabilisticPolicy::softmax(&q, 0.5);
+        let probs = soft.action_probabilities(0);
+        assert!((probs.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probs[1] > probs[2] && probs[2] > probs[0]);
+        assert!(soft
+            .action_probabilities(1)
+            .iter()
+            .all(|&p| (p - 1.0 / 3.0).abs() < 1e-12));
+    }
+}
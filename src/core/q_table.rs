@@ -0,0 +1,300 @@
+use crate::core::envs::Environment;
+use crate::core::policies::{
+    load_from_file, restrict_to_mask, save_to_file, DeterministicPolicy, Policy,
+    ProbabilisticPolicy,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+
+/// Au-delà de ce nombre de couples (s, a), `QTable::for_env` choisit le stockage creux
+pub const DENSE_LIMIT: usize = 1 << 24;
+
+/// Stockage des valeurs Q
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Backend {
+    /// `num_states * num_actions` valeurs contiguës
+    Dense(Vec<f64>),
+    /// Une ligne par état visité, les autres valent 0
+    Sparse(HashMap<usize, Vec<f64>>),
+}
+
+/// Table Q(s, a) partagée par les algorithmes MC, TD et de planning.
+/// Les valeurs non écrites valent 0 (ou la valeur passée à `fill`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QTable {
+    num_states: usize,
+    num_actions: usize,
+    backend: Backend,
+    /// Valeur des lignes absentes du stockage creux
+    #[serde(default)]
+    default_value: f64,
+}
+
+impl QTable {
+    pub fn dense(num_states: usize, num_actions: usize) -> Self {
+        Self {
+            num_states,
+            num_actions,
+            backend: Backend::Dense(vec![0.0; num_states * num_actions]),
+            default_value: 0.0,
+        }
+    }
+
+    pub fn sparse(num_states: usize, num_actions: usize) -> Self {
+        Self {
+            num_states,
+            num_actions,
+            backend: Backend::Sparse(HashMap::new()),
+            default_value: 0.0,
+        }
+    }
+
+    /// Table dense, ou creuse si l'environnement dépasse `DENSE_LIMIT` couples (s, a)
+    pub fn for_env(env: &dyn Environment) -> Self {
+        if env.num_states().saturating_mul(env.num_actions()) > DENSE_LIMIT {
+            Self::sparse(env.num_states(), env.num_actions())
+        } else {
+            Self::dense(env.num_states(), env.num_actions())
+        }
+    }
+
+    /// Construit une table dense à partir de `rows[s][a]`
+    pub fn from_rows(rows: &[Vec<f64>]) -> Self {
+        let num_actions = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == num_actions),
+            "Toutes les lignes doivent avoir le même nombre d'actions"
+        );
+        Self {
+            num_states: rows.len(),
+            num_actions,
+            backend: Backend::Dense(rows.concat()),
+            default_value: 0.0,
+        }
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    pub fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    pub fn is_dense(&self) -> bool {
+        matches!(self.backend, Backend::Dense(_))
+    }
+
+    fn check(&self, s: usize, a: usize) {
+        assert!(
+            s < self.num_states && a < self.num_actions,
+            "Couple ({}, {}) hors de la table {}x{}",
+            s,
+            a,
+            self.num_states,
+            self.num_actions
+        );
+    }
+
+    pub fn get(&self, s: usize, a: usize) -> f64 {
+        self.check(s, a);
+        match &self.backend {
+            Backend::Dense(values) => values[s * self.num_actions + a],
+            Backend::Sparse(rows) => rows.get(&s).map_or(self.default_value, |row| row[a]),
+        }
+    }
+
+    pub fn get_mut(&mut self, s: usize, a: usize) -> &mut f64 {
+        &mut self.row_mut(s)[a]
+    }
+
+    pub fn set(&mut self, s: usize, a: usize, value: f64) {
+        *self.get_mut(s, a) = value;
+    }
+
+    /// Valeurs Q(s, ·) de l'état donné
+    pub fn row(&self, s: usize) -> Cow<'_, [f64]> {
+        self.check(s, 0);
+        match &self.backend {
+            Backend::Dense(values) => {
+                Cow::Borrowed(&values[s * self.num_actions..(s + 1) * self.num_actions])
+            }
+            Backend::Sparse(rows) => match rows.get(&s) {
+                Some(row) => Cow::Borrowed(row),
+                None => Cow::Owned(vec![self.default_value; self.num_actions]),
+            },
+        }
+    }
+
+    pub fn row_mut(&mut self, s: usize) -> &mut [f64] {
+        self.check(s, 0);
+        let num_actions = self.num_actions;
+        let default_value = self.default_value;
+        match &mut self.backend {
+            Backend::Dense(values) => &mut values[s * num_actions..(s + 1) * num_actions],
+            Backend::Sparse(rows) => rows
+                .entry(s)
+                .or_insert_with(|| vec![default_value; num_actions]),
+        }
+    }
+
+    /// Donne la même valeur à tous les couples (s, a), par exemple pour une initialisation optimiste
+    pub fn fill(&mut self, value: f64) {
+        self.default_value = value;
+        match &mut self.backend {
+            Backend::Dense(values) => values.fill(value),
+            Backend::Sparse(rows) => rows.clear(),
+        }
+    }
+
+    /// Meilleure action parmi celles où `mask[a]` est vrai (la dernière en cas d'égalité,
+    /// comme `Iterator::max_by` utilisé jusque-là par les algorithmes)
+    pub fn argmax(&self, s: usize, mask: &[bool]) -> Option<usize> {
+        let row = self.row(s);
+        let mut best: Option<usize> = None;
+        for a in (0..self.num_actions).filter(|&a| mask[a]) {
+            if best.is_none_or(|b| row[a] >= row[b]) {
+                best = Some(a);
+            }
+        }
+        best
+    }
+
+    /// max Q(s, a) parmi les actions autorisées par `mask`
+    pub fn max(&self, s: usize, mask: &[bool]) -> Option<f64> {
+        self.argmax(s, mask).map(|a| self.get(s, a))
+    }
+
+    /// Meilleure action sur toutes les actions
+    pub fn best_action(&self, s: usize) -> usize {
+        self.argmax(s, &vec![true; self.num_actions]).unwrap_or(0)
+    }
+
+    /// max_a Q(s, a) sur toutes les actions
+    pub fn max_value(&self, s: usize) -> f64 {
+        self.get(s, self.best_action(s))
+    }
+
+    /// Policy gloutonne sur toutes les actions
+    pub fn greedy_policy(&self) -> DeterministicPolicy {
+        self.greedy_policy_masked(&vec![true; self.num_actions])
+    }
+
+    /// Policy gloutonne restreinte aux actions autorisées par `mask` (dans tous les états)
+    pub fn greedy_policy_masked(&self, mask: &[bool]) -> DeterministicPolicy {
+        let table = (0..self.num_states)
+            .map(|s| self.argmax(s, mask).unwrap_or(0))
+            .collect();
+        DeterministicPolicy::from_table(self.num_states, self.num_actions, table)
+    }
+
+    /// Policy ε-greedy dérivée de la table
+    pub fn epsilon_greedy_policy(&self, epsilon: f64) -> ProbabilisticPolicy {
+        ProbabilisticPolicy::epsilon_greedy(&self.to_rows(), epsilon)
+    }
+
+    /// Copie dense `rows[s][a]`
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        (0..self.num_states).map(|s| self.row(s).into_owned()).collect()
+    }
+
+    /// Couples (s, a, Q) stockés, par état croissant
+    pub fn entries(&self) -> Vec<(usize, usize, f64)> {
+        let mut states: Vec<usize> = match &self.backend {
+            Backend::Dense(_) => (0..self.num_states).collect(),
+            Backend::Sparse(rows) => rows.keys().copied().collect(),
+        };
+        states.sort_unstable();
+        states
+            .into_iter()
+            .flat_map(|s| {
+                let row = self.row(s).into_owned();
+                row.into_iter().enumerate().map(move |(a, q)| (s, a, q))
+            })
+            .collect()
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        save_to_file(self, path)
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        load_from_file(path)
+    }
+}
+
+/// Une Q-table se comporte comme sa policy gloutonne
+impl Policy for QTable {
+    fn get_action(&self, state: &usize) -> usize {
+        self.best_action(*state)
+    }
+
+    fn num_actions(&self) -> usize {
+        self.num_actions
+    }
+
+    fn action_probs(&self, state: usize, mask: &[bool]) -> Vec<f64> {
+        let mut probs = vec![0.0; self.num_actions];
+        if let Some(best) = self.argmax(state, mask) {
+            probs[best] = 1.0;
+        }
+        restrict_to_mask(probs, mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(q: &mut QTable) {
+        q.set(0, 1, 2.0);
+        q.set(0, 2, 3.0);
+        *q.get_mut(3, 0) += -1.0;
+    }
+
+    #[test]
+    fn test_dense_and_sparse_backends_agree() {
+        let mut dense = QTable::dense(4, 3);
+        let mut sparse = QTable::sparse(4, 3);
+        fill(&mut dense);
+        fill(&mut sparse);
+
+        assert_eq!(dense.to_rows(), sparse.to_rows());
+        assert_eq!(sparse.get(2, 1), 0.0);
+        assert_eq!(sparse.entries().len(), 6);
+        assert_eq!(dense.entries().len(), 12);
+
+        for q in [&dense, &sparse] {
+            assert_eq!(q.best_action(0), 2);
+            assert_eq!(q.argmax(0, &[true, true, false]), Some(1));
+            assert_eq!(q.max(0, &[true, false, false]), Some(0.0));
+            assert_eq!(q.max(0, &[false, false, false]), None);
+            // Égalité : la dernière action
+            assert_eq!(q.best_action(3), 2);
+            assert_eq!(q.greedy_policy().policy_table, vec![2, 2, 2, 2]);
+            assert_eq!(q.action_probs(0, &[true, true, false]), vec![0.0, 1.0, 0.0]);
+        }
+
+        let eps = sparse.epsilon_greedy_policy(0.3);
+        assert!((eps.get_proba(0, 2) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("q_table_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for mut q in [QTable::dense(4, 3), QTable::sparse(4, 3)] {
+            fill(&mut q);
+            let path = dir.join("q.json");
+            q.save(path.to_str().unwrap()).unwrap();
+            let loaded = QTable::load(path.to_str().unwrap()).unwrap();
+            assert_eq!(loaded.is_dense(), q.is_dense());
+            assert_eq!(loaded.to_rows(), q.to_rows());
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
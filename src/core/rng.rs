@@ -0,0 +1,96 @@
+//! Graines dérivées d'une graine maître : l'env, l'algorithme et l'évaluation ont chacun
+//! leur flux, pour qu'une expérience soit reproductible à partir de `Config::seed`.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Flux du RNG propre à l'environnement
+pub const ENV_STREAM: u64 = 1;
+/// Flux du RNG passé à l'algorithme d'apprentissage
+pub const ALGORITHM_STREAM: u64 = 2;
+/// Flux du RNG de l'évaluation de la policy apprise
+pub const EVALUATION_STREAM: u64 = 3;
+/// Flux du RNG interne des policies stochastiques (`Policy::get_action`)
+pub const POLICY_STREAM: u64 = 4;
+
+/// Graine maître de ce qui n'a pas reçu de graine explicite (celle de `Config::default()`)
+pub const DEFAULT_SEED: u64 = 0;
+
+/// Mélangeur SplitMix64
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Graine du flux `stream` dérivée de `master`
+pub fn derive_seed(master: u64, stream: u64) -> u64 {
+    splitmix64(master ^ splitmix64(stream))
+}
+
+/// RNG du flux `stream` dérivé de `master`
+pub fn seeded_rng(master: u64, stream: u64) -> StdRng {
+    StdRng::seed_from_u64(derive_seed(master, stream))
+}
+
+/// RNG du flux `stream` dérivé de `DEFAULT_SEED`, pour les envs et les policies qui n'ont pas
+/// encore reçu de graine : sans appel à `seed`, deux exécutions restent identiques
+pub fn default_rng(stream: u64) -> StdRng {
+    seeded_rng(DEFAULT_SEED, stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::exploration::EpsilonGreedy;
+    use crate::algorithms::schedule::Schedules;
+    use crate::algorithms::td::q_learning::q_learning;
+    use crate::core::envs::MonteCarloEnvironment;
+    use crate::environments::line_world::LineWorld;
+    use crate::environments::monty_hall_lvl1::new_monty_hall;
+
+    #[test]
+    fn test_streams_are_distinct_and_stable() {
+        assert_eq!(derive_seed(7, ENV_STREAM), derive_seed(7, ENV_STREAM));
+        assert_ne!(derive_seed(7, ENV_STREAM), derive_seed(7, ALGORITHM_STREAM));
+        assert_ne!(derive_seed(7, ENV_STREAM), derive_seed(8, ENV_STREAM));
+    }
+
+    #[test]
+    fn test_seeded_env_replays_the_same_games() {
+        let play = |seed: u64| {
+            let mut env = new_monty_hall(1);
+            env.seed(seed);
+            (0..20)
+                .map(|_| {
+                    env.reset();
+                    while !env.is_game_over() {
+                        env.step(env.available_actions()[0]);
+                    }
+                    env.score()
+                })
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(play(3), play(3));
+        assert_ne!(play(3), play(4));
+    }
+
+    #[test]
+    fn test_same_seed_same_training() {
+        let train = |seed: u64| {
+            let mut env = LineWorld::new();
+            let mut rng = seeded_rng(seed, ALGORITHM_STREAM);
+            let (_, q, rewards) = q_learning(
+                &mut env,
+                &mut Schedules::constant(0.1),
+                0.9,
+                &mut EpsilonGreedy::new(0.3),
+                50,
+                &mut rng,
+            );
+            (q.to_rows(), rewards)
+        };
+        assert_eq!(train(11), train(11));
+    }
+}
@@ -1,10 +1,24 @@
-use rand::{prelude::StdRng, seq::IndexedRandom, SeedableRng};
+use rand::{prelude::StdRng, SeedableRng};
+use std::error::Error;
+use std::path::{Path, PathBuf};
 
 use crate::{
     algorithms::{
+        exploration::Exploration,
+        schedule::{Schedule, Schedules},
         dp::{
+            average_reward::{average_reward_policy_iteration, relative_value_iteration, AverageRewardSolution},
+            finite_horizon::finite_horizon_dp,
+            linear_programming::linear_programming,
+            parallel::parallel_value_iteration_with_report,
             policy_iteration::policy_iteration,
-            value_iteration::value_iteration,
+            prioritized::prioritized_value_iteration,
+            q_value_iteration::q_value_iteration,
+            report::SolveReport,
+            sweeps::{
+                async_value_iteration, modified_policy_iteration, value_iteration_sweeps_with_report, DpOptions,
+                SweepMode, SweepStats,
+            },
         },
         mc::{
             mc_es::monte_carlo_es,
@@ -19,13 +33,21 @@ use crate::{
         },
     },
     core::{
-        envs::{DynamicProgramingEnvironment, MonteCarloEnvironment},
-        policies::{save_to_file, Policy},
+        artifact::{write_rewards_csv, Artifact, ArtifactMetadata, Payload},
+        envs::{DynamicProgramingEnvironment, Environment, MonteCarloEnvironment, SparseDPEnvironment},
+        policies::{save_to_file, DeterministicPolicy, NonStationaryPolicy, Policy},
+        q_table::QTable,
+        rng::{derive_seed, seeded_rng, ALGORITHM_STREAM, ENV_STREAM, EVALUATION_STREAM},
     },
     environments::{
-        grid_world::{dynamic_programming::grid_world, GridWorld},
+        grid_world::{
+            dynamic_programming::{grid_world, grid_world_sized},
+            GridWorld,
+        },
         line_world::{line_world_dp, LineWorld},
+        monty_hall_lvl1::{monty_hall_lvl1_dp, new_monty_hall},
         pierre_feuille_ciseaux::{pierre_feuille_ciseaux_dp, PierreFeuilleCiseaux},
+        plugin_env::{load_registered_plugin, register_plugin, PluginSpec},
         secret_envs::SecretEnv,
     },
 };
@@ -41,7 +63,26 @@ pub struct Config {
     pub kappa: f64,
     pub max_iter: usize,
     pub planning_steps: usize,
+    /// Schedules optionnels ; à défaut, `alpha`, `epsilon` et `kappa` restent constants
+    /// (alpha vaut 1/n(s, a) pour `on_policy_mc`)
+    pub alpha_schedule: Option<Schedule>,
+    pub epsilon_schedule: Option<Schedule>,
+    pub kappa_schedule: Option<Schedule>,
+    /// Variantes DP : balayages Jacobi/Gauss-Seidel, k de la PI modifiée, ordre de la VI asynchrone
+    pub dp: DpOptions,
+    /// Nombre de pas de `finite_horizon`, obligatoire pour cet algorithme
+    pub horizon: Option<usize>,
+    /// Stratégie d'exploration des algorithmes TD, Dyna et MC on-policy
+    pub exploration: Exploration,
+    /// Graine maître, enregistrée dans les artefacts : l'env, l'algorithme et
+    /// l'évaluation reçoivent chacun une graine dérivée (voir `core::rng`)
+    pub seed: u64,
     pub output_dir: String,
+    /// Plugins enregistrés sous leur nom avant de construire l'env : `env_name` peut
+    /// alors désigner l'un d'eux
+    pub plugins: Vec<(String, PluginSpec)>,
+    /// Cache des modèles DP des secret envs ; `dp_cache_dir()` à défaut
+    pub dp_cache_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -56,7 +97,16 @@ impl Default for Config {
             kappa: 0.001,
             max_iter: 1000,
             planning_steps: 10,
+            alpha_schedule: None,
+            epsilon_schedule: None,
+            kappa_schedule: None,
+            dp: DpOptions::default(),
+            horizon: None,
+            exploration: Exploration::default(),
+            seed: 0,
             output_dir: "output/default".into(),
+            plugins: Vec::new(),
+            dp_cache_dir: None,
         }
     }
 }
@@ -64,33 +114,38 @@ impl Default for Config {
 /// Résultat d'une expérience, pour un traitement uniforme
 /// P : Policy, SV : state values, QV : Q-values, RV : rewards vector
 enum ExperimentResult<P, SV, QV, RV> {
-    PolicyValues { policy: P, values: SV },
+    /// `stats` : coût en balayages des variantes DP qui le mesurent,
+    /// `report` : diagnostic de convergence des solveurs qui le produisent
+    PolicyValues { policy: P, values: SV, stats: Option<SweepStats>, report: Option<SolveReport> },
     PolicyQValues { policy: P, q_values: QV, rewards: RV },
-    PolicyOnly { policy: P, rewards: RV },
+    /// Horizon fini : π_t et V_t pour chaque pas t
+    FiniteHorizon { policy: NonStationaryPolicy, values: Vec<Vec<f64>> },
 }
 
-/// Évalue une policy sur un environnement MC pour calculer la récompense totale moyenne
-fn evaluate_policy(
+/// Nombre de pas maximal d'un épisode d'évaluation, pour les policies qui n'atteignent
+/// jamais un état terminal
+pub const MAX_EVALUATION_STEPS: usize = 10_000;
+
+/// Évalue une policy sur un environnement MC pour calculer la récompense totale moyenne.
+/// Le pas de l'épisode est transmis à la policy (`get_action_masked_at`). Un épisode s'arrête
+/// à un état terminal, après `policy.step_limit()` pas, ou après `max_steps` pas.
+pub fn evaluate_policy(
     env: &mut dyn MonteCarloEnvironment,
     policy: &dyn Policy,
     num_episodes: usize,
-    gamma: f64,
+    max_steps: usize,
+    seed: u64,
 ) -> f64 {
+    let max_steps = policy.step_limit().map_or(max_steps, |limit| limit.min(max_steps));
     let mut total_return = 0.0;
-    let mut rng = <StdRng as SeedableRng>::seed_from_u64(0);
+    let mut rng = <StdRng as SeedableRng>::seed_from_u64(seed);
     for _ in 0..num_episodes {
         env.reset();
         let mut g = 0.0;
         let mut t = 0;
-        loop {
+        while t < max_steps {
             let s = env.state_id();
-            let a = policy.get_action(&s);
-            let a = if !env.is_forbidden(a) {
-                a
-            } else {
-                println!("Forbidden action: {}, choosing random_action", a);
-                *env.available_actions().choose(&mut rng).unwrap()
-            };
+            let a = policy.get_action_masked_at(s, t, &env.available_actions(), &mut rng);
             let (_s_next, r) = env.step(a);
             g += r;
             t += 1;
@@ -103,119 +158,516 @@ fn evaluate_policy(
     total_return / num_episodes as f64
 }
 
-/// Lance l'entraînement selon la configuration et sauvegarde les résultats
+/// Affiche le gain et la structure de la chaîne ; le biais tient lieu de valeurs d'état
+fn average_reward_result(
+    solution: AverageRewardSolution,
+) -> ExperimentResult<DeterministicPolicy, Vec<f64>, QTable, Vec<f64>> {
+    match solution.unichain_gain() {
+        Some(gain) => println!("Gain : {:.6} ({})", gain, solution.structure),
+        None => println!("Gain par état : {:?} ({})", solution.gain, solution.structure),
+    }
+    ExperimentResult::PolicyValues { policy: solution.policy, values: solution.bias, stats: None, report: None }
+}
+
+/// Variable d'environnement qui fixe le répertoire du cache des modèles DP des secret envs
+pub const DP_CACHE_DIR_VAR: &str = "RL_DP_CACHE_DIR";
+
+/// Répertoire où sont mis en cache les modèles DP extraits des secret envs :
+/// `$RL_DP_CACHE_DIR`, sinon `rl_dp_cache` dans le répertoire temporaire, quel que soit le
+/// répertoire courant. Les entrées sont indexées par le hash de la lib.
+pub fn dp_cache_dir() -> PathBuf {
+    std::env::var_os(DP_CACHE_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("rl_dp_cache"))
+}
+
+fn secret_env_snapshot(env_id: usize, cache_dir: &Path) -> Result<SparseDPEnvironment, Box<dyn Error>> {
+    Ok(SecretEnv::new(env_id)?.dp_snapshot_cached(cache_dir)?)
+}
+
+/// Lance l'entraînement selon la configuration et sauvegarde les résultats.
+/// Les erreurs (env introuvable, écriture impossible, ...) sont affichées sans interrompre le processus.
 pub fn run_experiment(cfg: &Config) {
-    // Init environnements
-    let mut env_dp: Box<dyn DynamicProgramingEnvironment> = match cfg.env_name.as_str() {
+    if let Err(e) = try_run_experiment(cfg) {
+        eprintln!("Expérience échouée: {} - {} : {}", cfg.env_name, cfg.algorithm, e);
+    }
+}
+
+/// Lance une série d'expériences et renvoie le résultat de chacune : l'échec d'un env
+/// (ex. lib secrète absente) n'empêche pas les suivantes de tourner.
+pub fn run_experiments(cfgs: &[Config]) -> Vec<(String, Result<(), String>)> {
+    let results: Vec<(String, Result<(), String>)> = cfgs
+        .iter()
+        .map(|cfg| {
+            let name = format!("{} - {}", cfg.env_name, cfg.algorithm);
+            (name, try_run_experiment(cfg).map_err(|e| e.to_string()))
+        })
+        .collect();
+
+    println!("=== Résumé des expériences ===");
+    for (name, result) in &results {
+        match result {
+            Ok(()) => println!("  [OK]     {}", name),
+            Err(e) => println!("  [ÉCHEC]  {} : {}", name, e),
+        }
+    }
+
+    results
+}
+
+/// Dimensions d'un nom `grid_world_<lignes>x<colonnes>`
+fn parse_grid_size(env_name: &str) -> Option<(usize, usize)> {
+    let (rows, cols) = env_name.strip_prefix("grid_world_")?.split_once('x')?;
+    Some((rows.parse().ok()?, cols.parse().ok()?))
+}
+
+/// Instancie le modèle DP correspondant à un nom de `Config::env_name`. Pour les secret envs,
+/// il est extrait une fois de la lib puis relu depuis `dp_cache_dir()`. `grid_world_<l>x<c>`
+/// donne un grid world creux de taille quelconque (modèle DP uniquement).
+pub fn make_dp_env(env_name: &str) -> Result<Box<dyn DynamicProgramingEnvironment>, Box<dyn Error>> {
+    make_dp_env_cached(env_name, &dp_cache_dir())
+}
+
+/// Comme `make_dp_env`, avec le cache des secret envs dans `cache_dir`
+pub fn make_dp_env_cached(
+    env_name: &str,
+    cache_dir: &Path,
+) -> Result<Box<dyn DynamicProgramingEnvironment>, Box<dyn Error>> {
+    Ok(match env_name {
         "line_world" => Box::new(line_world_dp()),
         "grid_world" => Box::new(grid_world()),
         "pierre_feuille_ciseaux" => Box::new(pierre_feuille_ciseaux_dp()),
-        "secret_0" => Box::new(SecretEnv::new(0).unwrap()),
-        "secret_1" => Box::new(SecretEnv::new(1).unwrap()),
-        "secret_2" => Box::new(SecretEnv::new(2).unwrap()),
-        _ => panic!("Environnement DP inconnu: {}", cfg.env_name),
-    };
-    let mut env_mc: Box<dyn MonteCarloEnvironment> = match cfg.env_name.as_str() {
+        "monty_hall_lvl1" => Box::new(monty_hall_lvl1_dp()),
+        "secret_0" => Box::new(secret_env_snapshot(0, cache_dir)?),
+        "secret_1" => Box::new(secret_env_snapshot(1, cache_dir)?),
+        "secret_2" => Box::new(secret_env_snapshot(2, cache_dir)?),
+        name => match parse_grid_size(name) {
+            Some((rows, cols)) if rows >= 2 && cols >= 1 => Box::new(grid_world_sized(rows, cols)),
+            _ => match load_registered_plugin(name) {
+                Some(plugin) => Box::new(plugin?),
+                None => return Err(format!("Environnement DP inconnu: {}", name).into()),
+            },
+        },
+    })
+}
+
+/// Instancie l'environnement MC correspondant à un nom de `Config::env_name`
+pub fn make_mc_env(env_name: &str) -> Result<Box<dyn MonteCarloEnvironment>, Box<dyn Error>> {
+    Ok(match env_name {
         "line_world" => Box::new(LineWorld::new()),
         "grid_world" => Box::new(GridWorld::new()),
         "pierre_feuille_ciseaux" => Box::new(PierreFeuilleCiseaux::new()),
-        "secret_0" => Box::new(SecretEnv::new(0).unwrap()),
-        "secret_1" => Box::new(SecretEnv::new(1).unwrap()),
-        "secret_2" => Box::new(SecretEnv::new(2).unwrap()),
-        _ => panic!("Environnement MC inconnu: {}", cfg.env_name),
+        "monty_hall_lvl1" => Box::new(new_monty_hall(1)),
+        "secret_0" => Box::new(SecretEnv::new(0)?),
+        "secret_1" => Box::new(SecretEnv::new(1)?),
+        "secret_2" => Box::new(SecretEnv::new(2)?),
+        name => match load_registered_plugin(name) {
+            Some(plugin) => Box::new(plugin?),
+            None => return Err(format!("Environnement MC inconnu: {}", name).into()),
+        },
+    })
+}
+
+/// Métadonnées des artefacts produits par une expérience
+fn artifact_metadata(cfg: &Config, env: &dyn Environment) -> ArtifactMetadata {
+    let metadata = ArtifactMetadata::new(&cfg.env_name, env, &cfg.algorithm)
+        .hyperparameter("alpha", cfg.alpha)
+        .hyperparameter("epsilon", cfg.epsilon)
+        .hyperparameter("gamma", cfg.gamma)
+        .hyperparameter("theta", cfg.theta)
+        .hyperparameter("kappa", cfg.kappa)
+        .hyperparameter("max_iter", cfg.max_iter as f64)
+        .hyperparameter("planning_steps", cfg.planning_steps as f64);
+    let metadata = match cfg.horizon {
+        Some(horizon) => metadata.hyperparameter("horizon", horizon as f64),
+        None => metadata,
+    };
+    let metadata = match cfg.exploration {
+        Exploration::EpsilonGreedy => metadata,
+        Exploration::Boltzmann { temperature } => metadata.hyperparameter("temperature", temperature),
+        Exploration::Ucb { c } => metadata.hyperparameter("ucb_c", c),
+        Exploration::Optimistic { initial_value } => {
+            metadata.hyperparameter("optimistic_initial_value", initial_value)
+        }
+        Exploration::CountBonus { beta } => metadata.hyperparameter("count_bonus_beta", beta),
+    };
+    metadata.seed(cfg.seed)
+}
+
+/// Comme `run_experiment`, mais renvoie l'erreur au lieu de l'afficher
+pub fn try_run_experiment(cfg: &Config) -> Result<(), Box<dyn Error>> {
+    for (name, spec) in &cfg.plugins {
+        register_plugin(name, spec.clone())?;
+    }
+
+    // Init environnements
+    // Chaque environnement n'est construit que si l'algorithme en a besoin : le modèle DP pour
+    // les algorithmes DP, l'environnement MC pour les autres (`grid_world_<l>x<c>` n'a pas de
+    // version MC).
+    let cache_dir = cfg.dp_cache_dir.clone().unwrap_or_else(dp_cache_dir);
+    let dp_env = || make_dp_env_cached(&cfg.env_name, &cache_dir);
+    let mc_env = || -> Result<Box<dyn MonteCarloEnvironment>, Box<dyn Error>> {
+        let mut env = make_mc_env(&cfg.env_name)?;
+        env.seed(derive_seed(cfg.seed, ENV_STREAM));
+        Ok(env)
     };
+    let mut dp_model: Option<Box<dyn DynamicProgramingEnvironment>> = None;
+    let mut mc_model: Option<Box<dyn MonteCarloEnvironment>> = None;
+    let mut rng = seeded_rng(cfg.seed, ALGORITHM_STREAM);
 
+    let epsilon = cfg.epsilon_schedule.clone().unwrap_or(Schedule::Constant(cfg.epsilon));
+    let mut strategy = cfg.exploration.build(epsilon);
+    let alpha = cfg.alpha_schedule.clone().unwrap_or(Schedule::Constant(cfg.alpha));
+    let kappa = cfg.kappa_schedule.clone().unwrap_or(Schedule::Constant(cfg.kappa));
+    let mut schedules = Schedules::new(alpha).with_kappa(kappa);
     let start = std::time::Instant::now();
     // Exécution
     let result = match cfg.algorithm.as_str() {
         "policy_iteration" => {
-            let (policy, values) = policy_iteration(&*env_dp, cfg.theta, cfg.gamma, cfg.max_iter);
-            ExperimentResult::PolicyValues { policy, values }
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let (policy, values, report) = policy_iteration(env_dp, cfg.theta, cfg.gamma, cfg.max_iter);
+            ExperimentResult::PolicyValues { policy, values, stats: None, report: Some(report) }
         }
         "value_iteration" => {
-            let (policy, values) = value_iteration(&*env_dp, cfg.theta, cfg.gamma, cfg.max_iter);
-            ExperimentResult::PolicyValues { policy, values }
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let (policy, values, stats, report) = if cfg.dp.sweep == SweepMode::Jacobi && cfg.dp.threads != 1 {
+                // Les threads partagent le modèle : copie creuse, qui est Sync
+                let env_dp = SparseDPEnvironment::from_env(env_dp);
+                parallel_value_iteration_with_report(&env_dp, cfg.theta, cfg.gamma, cfg.max_iter, cfg.dp.threads)
+            } else {
+                value_iteration_sweeps_with_report(env_dp, cfg.theta, cfg.gamma, cfg.max_iter, cfg.dp.sweep)
+            };
+            ExperimentResult::PolicyValues { policy, values, stats: Some(stats), report: Some(report) }
+        }
+        "modified_policy_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let (policy, values, stats) = modified_policy_iteration(
+                env_dp,
+                cfg.theta,
+                cfg.gamma,
+                cfg.max_iter,
+                cfg.dp.evaluation_sweeps,
+                cfg.dp.sweep,
+            );
+            ExperimentResult::PolicyValues { policy, values, stats: Some(stats), report: None }
+        }
+        "async_value_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let (policy, values, stats) = async_value_iteration(
+                env_dp,
+                cfg.theta,
+                cfg.gamma,
+                cfg.max_iter,
+                cfg.dp.state_order,
+                &mut rng,
+            );
+            ExperimentResult::PolicyValues { policy, values, stats: Some(stats), report: None }
+        }
+        "prioritized_value_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            // max_iter balayages complets au plus
+            let max_backups = cfg.max_iter.saturating_mul(env_dp.num_states());
+            let (policy, values, stats) =
+                prioritized_value_iteration(env_dp, cfg.theta, cfg.gamma, max_backups);
+            ExperimentResult::PolicyValues { policy, values, stats: Some(stats), report: None }
+        }
+        "linear_programming" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let lp = linear_programming(env_dp, cfg.gamma)?;
+            println!("Simplexe : {} pivots, objectif μᵀV* = {:.6}", lp.pivots, lp.objective);
+            ExperimentResult::PolicyValues { policy: lp.policy, values: lp.values, stats: None, report: None }
+        }
+        "q_value_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let result = q_value_iteration(
+                env_dp,
+                cfg.theta,
+                cfg.gamma,
+                cfg.max_iter,
+                cfg.dp.tie_tolerance,
+            );
+            let ties = result.optimal_actions.iter().filter(|a| a.len() > 1).count();
+            println!("États à plusieurs actions optimales : {}", ties);
+            // Pas d'épisodes : rewards.csv n'est pas écrit
+            ExperimentResult::PolicyQValues { policy: result.policy, q_values: result.q, rewards: Vec::new() }
+        }
+        "relative_value_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let solution = relative_value_iteration(env_dp, cfg.theta, cfg.max_iter);
+            average_reward_result(solution)
+        }
+        "average_reward_policy_iteration" => {
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let solution = average_reward_policy_iteration(env_dp, cfg.max_iter);
+            average_reward_result(solution)
+        }
+        "finite_horizon" => {
+            let horizon = cfg
+                .horizon
+                .filter(|&h| h >= 1)
+                .ok_or("finite_horizon demande un horizon d'au moins un pas")?;
+            let env_dp = &**dp_model.insert(dp_env()?);
+            let (policy, values) = finite_horizon_dp(env_dp, horizon);
+            ExperimentResult::FiniteHorizon { policy, values }
         }
         "mc_es" => {
-            let (policy, q_values, rewards) = monte_carlo_es(&mut *env_mc, cfg.max_iter, cfg.gamma);
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = monte_carlo_es(&mut *env_mc, cfg.max_iter, cfg.gamma, &mut rng);
             ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "on_policy_mc" => {
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            // Sans schedule explicite : moyenne empirique des retours
+            schedules.alpha = cfg.alpha_schedule.clone().unwrap_or(Schedule::VisitCount);
             let (policy, q_values, rewards) = on_policy_first_visit_mc_control(
                 &mut *env_mc,
                 cfg.max_iter,
                 cfg.gamma,
-                cfg.epsilon,
+                &mut schedules,
+                &mut *strategy,
+                &mut rng,
             );
             ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "off_policy_mc" => {
+            let env_mc = &mut **mc_model.insert(mc_env()?);
             let (policy, q_values, rewards) = off_policy_mc_control(
                 &mut *env_mc,
                 cfg.max_iter,
                 cfg.gamma,
                 cfg.epsilon,
+                &mut rng,
             );
             ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "sarsa" => {
-            let (policy, rewards) = sarsa(&mut *env_mc, cfg.alpha, cfg.gamma, cfg.epsilon, cfg.max_iter);
-            ExperimentResult::PolicyOnly { policy, rewards }
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = sarsa(&mut *env_mc, &mut schedules, cfg.gamma, &mut *strategy, cfg.max_iter, &mut rng);
+            ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "exp_sarsa" => {
-            let (policy, rewards) = expected_sarsa(&mut *env_mc, cfg.alpha, cfg.gamma, cfg.epsilon, cfg.max_iter);
-            ExperimentResult::PolicyOnly { policy, rewards }
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = expected_sarsa(&mut *env_mc, &mut schedules, cfg.gamma, &mut *strategy, cfg.max_iter, &mut rng);
+            ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "q_learning" => {
-            let (policy, rewards) = q_learning(&mut *env_mc, cfg.alpha, cfg.gamma, cfg.epsilon, cfg.max_iter);
-            ExperimentResult::PolicyOnly { policy, rewards }
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = q_learning(&mut *env_mc, &mut schedules, cfg.gamma, &mut *strategy, cfg.max_iter, &mut rng);
+            ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "dyna_q" => {
-            let (policy, rewards) = dyna_q(&mut *env_mc, cfg.alpha, cfg.gamma, cfg.epsilon, cfg.planning_steps, cfg.max_iter);
-            ExperimentResult::PolicyOnly { policy, rewards }
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = dyna_q(&mut *env_mc, &mut schedules, cfg.gamma, &mut *strategy, cfg.planning_steps, cfg.max_iter, &mut rng);
+            ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
         "dyna_q_plus" => {
-            let (policy, rewards) = dyna_q_plus(&mut *env_mc, cfg.alpha, cfg.gamma, cfg.epsilon, cfg.kappa, cfg.planning_steps, cfg.max_iter);
-            ExperimentResult::PolicyOnly { policy, rewards }
+            let env_mc = &mut **mc_model.insert(mc_env()?);
+            let (policy, q_values, rewards) = dyna_q_plus(&mut *env_mc, &mut schedules, cfg.gamma, &mut *strategy, cfg.planning_steps, cfg.max_iter, &mut rng);
+            ExperimentResult::PolicyQValues { policy, q_values, rewards }
         }
-        _ => panic!("Algorithme inconnu: {}", cfg.algorithm),
+        _ => return Err(format!("Algorithme inconnu: {}", cfg.algorithm).into()),
     };
 
     let duration = start.elapsed();
     println!("Durée de l'entraînement : {:?}", duration);
 
     // Création dossier sortie
-    std::fs::create_dir_all(&cfg.output_dir).expect("Impossible de créer le répertoire");
+    std::fs::create_dir_all(&cfg.output_dir)?;
 
-    // Sauvegarde
+    // Sauvegarde : artefacts JSON versionnés + exports CSV
     let eval_episodes = 1000;
-    match result {
-        ExperimentResult::PolicyValues { policy, values } => {
-            save_to_file(&policy, &format!("{}/policy.json", cfg.output_dir)).unwrap();
-            save_to_file(&values, &format!("{}/values.csv", cfg.output_dir)).unwrap();
-            let avg = evaluate_policy(&mut *env_mc, &policy, eval_episodes, cfg.gamma);
-            save_to_file(&avg, &format!("{}/avg_reward.txt", cfg.output_dir)).unwrap();
-            save_to_file(&duration, &format!("{}/duration.txt", cfg.output_dir)).unwrap();
-            println!("Avg reward ({} eps): {:.4}", eval_episodes, avg);
+    let eval_seed = derive_seed(cfg.seed, EVALUATION_STREAM);
+    let out = |name: &str| format!("{}/{}", cfg.output_dir, name);
+    // Évaluation par épisodes sur l'environnement MC, s'il existe
+    let mut env_mc = match mc_model {
+        Some(env) => Some(env),
+        None => match mc_env() {
+            Ok(env) => Some(env),
+            Err(e) => {
+                println!("Pas d'évaluation par épisodes ({})", e);
+                None
+            }
+        },
+    };
+    let metadata = match (&dp_model, &env_mc) {
+        (Some(env), _) => artifact_metadata(cfg, &**env),
+        (None, Some(env)) => artifact_metadata(cfg, &**env),
+        (None, None) => unreachable!("chaque algorithme construit son environnement"),
+    };
+    let mut evaluate = |policy: &dyn Policy| {
+        env_mc
+            .as_deref_mut()
+            .map(|env| {
+                evaluate_policy(env, policy, eval_episodes, MAX_EVALUATION_STEPS, eval_seed)
+            })
+    };
+    let avg = match result {
+        ExperimentResult::PolicyValues { policy, values, stats, report } => {
+            if let Some(report) = report {
+                println!("{}", report);
+                save_to_file(&report, &out("solve_report.json"))?;
+            }
+            if let Some(stats) = stats {
+                println!(
+                    "Balayages : {}, backups : {}, résidu : {:e}",
+                    stats.sweeps, stats.backups, stats.residual
+                );
+                save_to_file(&stats, &out("sweep_stats.json"))?;
+            }
+            let avg = evaluate(&policy);
+            let values = Artifact::new(metadata.clone(), Payload::StateValues(values));
+            values.save(&out("values.json"))?;
+            values.write_csv(&out("values.csv"))?;
+            let policy = Artifact::new(metadata, Payload::DeterministicPolicy(policy));
+            policy.save(&out("policy.json"))?;
+            policy.write_csv(&out("policy.csv"))?;
+            avg
         }
         ExperimentResult::PolicyQValues { policy, q_values, rewards } => {
-            save_to_file(&policy, &format!("{}/policy.json", cfg.output_dir)).unwrap();
-            save_to_file(&q_values, &format!("{}/q_values.csv", cfg.output_dir)).unwrap();
-            save_to_file(&rewards, &format!("{}/rewards.csv", cfg.output_dir)).unwrap();
-            let avg = evaluate_policy(&mut *env_mc, &policy, eval_episodes, cfg.gamma);
-            save_to_file(&avg, &format!("{}/avg_reward.txt", cfg.output_dir)).unwrap();
-            save_to_file(&duration, &format!("{}/duration.txt", cfg.output_dir)).unwrap();
-            println!("Avg reward ({} eps): {:.4}", eval_episodes, avg);
-        }
-        ExperimentResult::PolicyOnly { policy, rewards } => {
-            save_to_file(&policy, &format!("{}/policy.json", cfg.output_dir)).unwrap();
-            save_to_file(&rewards, &format!("{}/rewards.csv", cfg.output_dir)).unwrap();
-            let avg = evaluate_policy(&mut *env_mc, &policy, eval_episodes, cfg.gamma);
-            save_to_file(&avg, &format!("{}/avg_reward.txt", cfg.output_dir)).unwrap();
-            save_to_file(&duration, &format!("{}/duration.txt", cfg.output_dir)).unwrap();
-            println!("Avg reward ({} eps): {:.4}", eval_episodes, avg);
+            let avg = evaluate(&policy);
+            let q_values = Artifact::new(metadata.clone(), Payload::QValues(q_values));
+            q_values.save(&out("q_values.json"))?;
+            q_values.write_csv(&out("q_values.csv"))?;
+            let policy = Artifact::new(metadata, Payload::DeterministicPolicy(policy));
+            policy.save(&out("policy.json"))?;
+            policy.write_csv(&out("policy.csv"))?;
+            if !rewards.is_empty() {
+                write_rewards_csv(&rewards, &out("rewards.csv"))?;
+            }
+            if !schedules.log.is_empty() {
+                schedules.log.write_csv(&out("schedules.csv"))?;
+            }
+            avg
+        }
+        ExperimentResult::FiniteHorizon { policy, values } => {
+            let avg = evaluate(&policy);
+            // values.json : V_0, la valeur de l'épisode entier depuis chaque état
+            let values = Artifact::new(metadata.clone(), Payload::StateValues(values[0].clone()));
+            values.save(&out("values.json"))?;
+            values.write_csv(&out("values.csv"))?;
+            let policy = Artifact::new(metadata, Payload::NonStationaryPolicy(policy));
+            policy.save(&out("policy.json"))?;
+            policy.write_csv(&out("policy.csv"))?;
+            avg
         }
+    };
+    save_to_file(&duration, &out("duration.txt"))?;
+    if let Some(avg) = avg {
+        save_to_file(&avg, &out("avg_reward.txt"))?;
+        println!("Avg reward ({} eps): {:.4}", eval_episodes, avg);
     }
 
     println!("Expérience terminée: {} - {} -> {}", cfg.env_name, cfg.algorithm, cfg.output_dir);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_experiments_reports_failures_per_env() {
+        let cfgs = vec![
+            Config {
+                env_name: "env_inconnu".into(),
+                algorithm: "q_learning".into(),
+                ..Default::default()
+            },
+            Config {
+                env_name: "line_world".into(),
+                algorithm: "algo_inconnu".into(),
+                ..Default::default()
+            },
+        ];
+
+        let results = run_experiments(&cfgs);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].1.as_ref().unwrap_err().contains("env_inconnu"));
+        assert!(results[1].1.as_ref().unwrap_err().contains("algo_inconnu"));
+    }
+
+    #[test]
+    fn test_dp_solver_runs_on_sized_grid_without_mc_counterpart() {
+        let output_dir = std::env::temp_dir().join(format!("sized_grid_runner_{}", std::process::id()));
+        let cfg = Config {
+            env_name: "grid_world_4x6".into(),
+            algorithm: "value_iteration".into(),
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        try_run_experiment(&cfg).unwrap();
+
+        let values = Artifact::load(output_dir.join("values.json").to_str().unwrap()).unwrap();
+        assert_eq!(values.metadata.num_states, grid_world_sized(4, 6).num_states());
+        // Pas d'environnement MC : pas d'évaluation par épisodes
+        assert!(!output_dir.join("avg_reward.txt").exists());
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        // Les algorithmes MC ont toujours besoin de la version MC
+        let err = try_run_experiment(&Config { algorithm: "q_learning".into(), ..cfg }).unwrap_err();
+        assert!(err.to_string().contains("Environnement MC inconnu"));
+    }
+
+    #[test]
+    fn test_finite_horizon_runs_through_the_runner() {
+        let output_dir = std::env::temp_dir().join(format!("finite_horizon_runner_{}", std::process::id()));
+        let cfg = Config {
+            env_name: "line_world".into(),
+            algorithm: "finite_horizon".into(),
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        assert!(try_run_experiment(&cfg).unwrap_err().to_string().contains("horizon"));
+
+        try_run_experiment(&Config { horizon: Some(3), ..cfg }).unwrap();
+        let policy = Artifact::load(output_dir.join("policy.json").to_str().unwrap()).unwrap();
+        assert!(matches!(policy.payload, Payload::NonStationaryPolicy(ref p) if p.horizon() == 3));
+        assert_eq!(policy.metadata.hyperparameters["horizon"], 3.0);
+        // Depuis le centre, deux pas à droite suffisent : l'évaluation gagne toujours
+        let avg: f64 = std::fs::read_to_string(output_dir.join("avg_reward.txt")).unwrap().trim().parse().unwrap();
+        assert_eq!(avg, 1.0);
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        // Horizon trop court pour atteindre le but (8 pas) : l'évaluation s'arrête à l'horizon
+        let output_dir = std::env::temp_dir().join(format!("finite_horizon_grid_{}", std::process::id()));
+        let cfg = Config {
+            env_name: "grid_world".into(),
+            algorithm: "finite_horizon".into(),
+            horizon: Some(3),
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        try_run_experiment(&cfg).unwrap();
+        let avg: f64 = std::fs::read_to_string(output_dir.join("avg_reward.txt")).unwrap().trim().parse().unwrap();
+        assert_eq!(avg, 0.0);
+        std::fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_evaluator_follows_non_stationary_policy() {
+        use crate::core::policies::NonStationaryPolicy;
+
+        // Droite au premier pas, puis gauche : 2 -> 3 -> 2 -> 1 -> 0, défaite
+        let mut tables = vec![vec![0; 5]; 4];
+        tables[0] = vec![1; 5];
+        let policy = NonStationaryPolicy::new(5, 2, tables);
+        let mut env = LineWorld::new();
+        assert_eq!(evaluate_policy(&mut env, &policy, 3, 100, 0), -1.0);
+
+        // Deux fois à droite : victoire
+        let policy = NonStationaryPolicy::new(5, 2, vec![vec![1; 5], vec![1; 5], vec![0; 5]]);
+        assert_eq!(evaluate_policy(&mut env, &policy, 3, 100, 0), 1.0);
+
+        // L'épisode s'arrête à l'horizon de la policy : 2 -> 3 -> 2, sans récompense
+        let policy = NonStationaryPolicy::new(5, 2, vec![vec![1; 5], vec![0; 5]]);
+        assert_eq!(evaluate_policy(&mut env, &policy, 3, 100, 0), 0.0);
+    }
+
+    #[test]
+    fn test_evaluator_caps_episodes_that_never_end() {
+        // Toujours vers le haut : le grid world ne termine jamais
+        let policy = DeterministicPolicy::from_table(25, 4, vec![0; 25]);
+        let mut env = GridWorld::new();
+        assert_eq!(evaluate_policy(&mut env, &policy, 2, 50, 0), 0.0);
+    }
 }
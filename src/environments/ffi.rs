@@ -0,0 +1,244 @@
+//! Export des environnements de la crate via une interface C, avec la même convention
+//! de symboles que `SecretEnv` (`<prefix>_new`, `<prefix>_step`, `<prefix>_state_id`, ...)
+//! et un `<prefix>_manifest` lisible par `PluginEnv`. Tous déclarent SEED : `<prefix>_seed`
+//! transmet la graine à `MonteCarloEnvironment::seed`.
+//!
+//! Préfixes exportés : `line_world`, `grid_world`, `pierre_feuille_ciseaux` et
+//! `monty_hall_lvl1`, chacun avec son modèle DP (`<prefix>_transition_probability`,
+//! `<prefix>_reward`), numéroté comme son env MC.
+
+use crate::core::envs::{DPEnvironment, DynamicProgramingEnvironment, Environment, MonteCarloEnvironment};
+use crate::environments::grid_world::{dynamic_programming::grid_world, GridWorld};
+use crate::environments::line_world::{line_world_dp, LineWorld};
+use crate::environments::monty_hall_lvl1::{monty_hall_lvl1_dp, new_monty_hall, MontyHallEnv};
+use crate::environments::pierre_feuille_ciseaux::{pierre_feuille_ciseaux_dp, PierreFeuilleCiseaux};
+use crate::environments::plugin_env::{PluginCapabilities, PluginManifest};
+use std::ffi::c_void;
+use std::sync::OnceLock;
+
+macro_rules! export_env {
+    ($module:ident, $prefix:literal, $env:ty, $new:expr, $caps:expr) => {
+        pub mod $module {
+            use super::*;
+
+            fn new_env() -> $env {
+                $new
+            }
+
+            /// # Safety
+            /// `env` doit provenir de `_new` ou `_from_random_state` et ne pas avoir été libéré.
+            unsafe fn env_ref<'a>(env: *const c_void) -> &'a $env {
+                &*(env as *const $env)
+            }
+
+            /// # Safety
+            /// `env` doit provenir de `_new` ou `_from_random_state` et ne pas avoir été libéré.
+            unsafe fn env_mut<'a>(env: *mut c_void) -> &'a mut $env {
+                &mut *(env as *mut $env)
+            }
+
+            #[export_name = concat!($prefix, "_manifest")]
+            pub extern "C" fn manifest() -> PluginManifest {
+                PluginManifest::new($caps)
+            }
+
+            /// (états, actions, rewards), lus sur une instance construite une seule fois
+            fn dimensions() -> (usize, usize, usize) {
+                static DIMENSIONS: OnceLock<(usize, usize, usize)> = OnceLock::new();
+                *DIMENSIONS.get_or_init(|| {
+                    let env = new_env();
+                    (env.num_states(), env.num_actions(), env.num_rewards())
+                })
+            }
+
+            #[export_name = concat!($prefix, "_num_states")]
+            pub extern "C" fn num_states() -> usize {
+                dimensions().0
+            }
+
+            #[export_name = concat!($prefix, "_num_actions")]
+            pub extern "C" fn num_actions() -> usize {
+                dimensions().1
+            }
+
+            #[export_name = concat!($prefix, "_num_rewards")]
+            pub extern "C" fn num_rewards() -> usize {
+                dimensions().2
+            }
+
+            #[export_name = concat!($prefix, "_new")]
+            pub extern "C" fn new() -> *mut c_void {
+                Box::into_raw(Box::new(new_env())) as *mut c_void
+            }
+
+            #[export_name = concat!($prefix, "_from_random_state")]
+            pub extern "C" fn from_random_state() -> *mut c_void {
+                let mut env = new_env();
+                env.start_from_random_state();
+                Box::into_raw(Box::new(env)) as *mut c_void
+            }
+
+            /// # Safety
+            /// `env` doit provenir de `_new` ou `_from_random_state` et n'est plus utilisable ensuite.
+            #[export_name = concat!($prefix, "_delete")]
+            pub unsafe extern "C" fn delete(env: *mut c_void) {
+                if !env.is_null() {
+                    drop(Box::from_raw(env as *mut $env));
+                }
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_reset")]
+            pub unsafe extern "C" fn reset(env: *mut c_void) {
+                env_mut(env).reset();
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_seed")]
+            pub unsafe extern "C" fn seed(env: *mut c_void, seed: u64) {
+                env_mut(env).seed(seed);
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_step")]
+            pub unsafe extern "C" fn step(env: *mut c_void, action: usize) {
+                env_mut(env).step(action);
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_score")]
+            pub unsafe extern "C" fn score(env: *const c_void) -> f32 {
+                env_ref(env).score() as f32
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_is_game_over")]
+            pub unsafe extern "C" fn is_game_over(env: *const c_void) -> bool {
+                env_ref(env).is_game_over()
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_state_id")]
+            pub unsafe extern "C" fn state_id(env: *const c_void) -> usize {
+                env_ref(env).state_id()
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_is_forbidden")]
+            pub unsafe extern "C" fn is_forbidden(env: *const c_void, action: usize) -> bool {
+                env_ref(env).is_forbidden(action)
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_display")]
+            pub unsafe extern "C" fn display(env: *const c_void) {
+                env_ref(env).display();
+            }
+
+            /// Le tableau renvoyé appartient à l'appelant et doit être libéré avec `_available_actions_delete`.
+            ///
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_available_actions")]
+            pub unsafe extern "C" fn available_actions(env: *const c_void) -> *const usize {
+                let actions = env_ref(env).available_actions().into_boxed_slice();
+                Box::into_raw(actions) as *const usize
+            }
+
+            /// # Safety
+            /// `env` doit être une instance valide.
+            #[export_name = concat!($prefix, "_available_actions_len")]
+            pub unsafe extern "C" fn available_actions_len(env: *const c_void) -> usize {
+                env_ref(env).available_actions().len()
+            }
+
+            /// # Safety
+            /// `actions` et `len` doivent provenir d'un même appel à `_available_actions` / `_available_actions_len`.
+            #[export_name = concat!($prefix, "_available_actions_delete")]
+            pub unsafe extern "C" fn available_actions_delete(actions: *const usize, len: usize) {
+                if !actions.is_null() {
+                    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                        actions as *mut usize,
+                        len,
+                    )));
+                }
+            }
+        }
+    };
+    ($module:ident, $prefix:literal, $env:ty, $new:expr, $caps:expr, dp: $dp:expr) => {
+        export_env!($module, $prefix, $env, $new, $caps);
+
+        pub mod dp {
+            use super::*;
+
+            fn model() -> &'static DPEnvironment {
+                static MODEL: OnceLock<DPEnvironment> = OnceLock::new();
+                MODEL.get_or_init(|| $dp)
+            }
+
+            #[export_name = concat!($prefix, "_transition_probability")]
+            pub extern "C" fn transition_probability(
+                state: usize,
+                action: usize,
+                state_prime: usize,
+                reward_index: usize,
+            ) -> f32 {
+                model().get_transition_prob(state, action, state_prime, reward_index) as f32
+            }
+
+            #[export_name = concat!($prefix, "_reward")]
+            pub extern "C" fn reward(i: usize) -> f32 {
+                model().get_reward(i) as f32
+            }
+        }
+    };
+}
+
+const FULL: PluginCapabilities = PluginCapabilities(
+    PluginCapabilities::MC.0
+        | PluginCapabilities::DP.0
+        | PluginCapabilities::SEED.0
+        | PluginCapabilities::RENDER.0,
+);
+
+pub mod line_world {
+    use super::*;
+    export_env!(mc, "line_world", LineWorld, LineWorld::new(), FULL, dp: line_world_dp());
+}
+
+pub mod grid_world {
+    use super::*;
+    export_env!(mc, "grid_world", GridWorld, GridWorld::new(), FULL, dp: grid_world());
+}
+
+pub mod pierre_feuille_ciseaux {
+    use super::*;
+    export_env!(
+        mc,
+        "pierre_feuille_ciseaux",
+        PierreFeuilleCiseaux,
+        PierreFeuilleCiseaux::new(),
+        FULL,
+        dp: pierre_feuille_ciseaux_dp()
+    );
+}
+
+pub mod monty_hall_lvl1 {
+    use super::*;
+    export_env!(
+        mc,
+        "monty_hall_lvl1",
+        MontyHallEnv,
+        new_monty_hall(1),
+        FULL,
+        dp: monty_hall_lvl1_dp()
+    );
+}
@@ -1,5 +1,5 @@
 pub mod dynamic_programming {
-    use crate::core::envs::{DPEnvironment, DynamicProgramingEnvironment};
+    use crate::core::envs::{DPEnvironment, DynamicProgramingEnvironment, SparseDPEnvironment};
 
     pub fn grid_world() -> DPEnvironment {
         let num_states = 25;
@@ -52,18 +52,62 @@ pub mod dynamic_programming {
 
         env
     }
+
+    /// Grid world de `rows` x `cols` cases avec la même dynamique que `grid_world` : le coin en
+    /// haut à droite est terminal (-3), le coin en bas à droite aussi (+1). Stockage creux, pour
+    /// les grilles de plusieurs milliers d'états.
+    pub fn grid_world_sized(rows: usize, cols: usize) -> SparseDPEnvironment {
+        assert!(rows >= 2 && cols >= 1, "La grille doit avoir au moins 2 lignes");
+        let num_states = rows * cols;
+        let lose = cols - 1;
+        let win = num_states - 1;
+        let rewards = vec![-3.0, 0.0, 1.0];
+        let mut env = SparseDPEnvironment::new(num_states, 4, 3, rewards, vec![lose, win]);
+
+        let reward_index = |s: usize| match s {
+            s if s == lose => 0,
+            s if s == win => 2,
+            _ => 1,
+        };
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let s = row * cols + col;
+                if s == lose || s == win {
+                    continue;
+                }
+                let next_states = [
+                    if row > 0 { s - cols } else { s },
+                    if row + 1 < rows { s + cols } else { s },
+                    if col > 0 { s - 1 } else { s },
+                    if col + 1 < cols { s + 1 } else { s },
+                ];
+                for (a, &next_state) in next_states.iter().enumerate() {
+                    env.set_transition_prob(s, a, next_state, reward_index(next_state), 1.0);
+                }
+            }
+        }
+
+        env
+    }
 }
 
 use crate::core::envs::{Environment, MonteCarloEnvironment};
-use rand::random_range;
+use crate::core::rng::{default_rng, ENV_STREAM};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 pub struct GridWorld {
     agent_pos: usize,
+    rng: StdRng,
 }
 
 impl GridWorld {
     pub fn new() -> Self {
-        let mut env = Self { agent_pos: 0 };
+        let mut env = Self {
+            agent_pos: 0,
+            rng: default_rng(ENV_STREAM),
+        };
         env.reset();
         env
     }
@@ -158,7 +202,11 @@ impl MonteCarloEnvironment for GridWorld {
     }
 
     fn start_from_random_state(&mut self) {
-        self.agent_pos = random_range(0..self.num_states())
+        self.agent_pos = self.rng.random_range(0..self.num_states())
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 
     fn state_id(&self) -> usize {
@@ -206,14 +254,14 @@ mod tests {
     use crate::core::envs::MonteCarloEnvironment;
     #[test]
     fn test_initial_state() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         assert_eq!(env.agent_pos, 0); // En haut à gauche (0,0)
     }
 
     #[test]
     fn test_valid_transition() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         env.step(3); // droite depuis (0,0)
         assert_eq!(env.agent_pos, 1); // (0,1)
@@ -223,7 +271,7 @@ mod tests {
 
     #[test]
     fn test_wall_collision() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         env.step(2); // gauche depuis (0,0)
         assert_eq!(env.agent_pos, 0); // reste sur place
@@ -233,7 +281,7 @@ mod tests {
 
     #[test]
     fn test_terminal_state_negative_reward() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         env.step(3); // (0,0) → (0,1)
         env.step(3); // → (0,2)
@@ -246,7 +294,7 @@ mod tests {
 
     #[test]
     fn test_terminal_state_positive_reward() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         // Move down 4 times to reach (4,0)
         for _ in 0..4 {
@@ -263,7 +311,7 @@ mod tests {
 
     #[test]
     fn test_display() {
-        let mut env = GridWorld { agent_pos: 0 };
+        let mut env = GridWorld { agent_pos: 0, rng: default_rng(ENV_STREAM) };
         env.reset();
         env.display();
     }
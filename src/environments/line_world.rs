@@ -1,15 +1,21 @@
 use crate::core::envs;
 use crate::core::envs::{DPEnvironment, DynamicProgramingEnvironment, Environment, MonteCarloEnvironment};
-use rand::random_range;
+use crate::core::rng::{default_rng, ENV_STREAM};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 #[derive(Debug)]
 pub struct LineWorld {
     agent_pos: usize,
+    rng: StdRng,
 }
 
 impl LineWorld {
     pub fn new() -> LineWorld {
-        let mut env = LineWorld { agent_pos: 0 };
+        let mut env = LineWorld {
+            agent_pos: 0,
+            rng: default_rng(ENV_STREAM),
+        };
         env.reset();
         env
     }
@@ -69,7 +75,11 @@ impl envs::MonteCarloEnvironment for LineWorld {
 
     fn start_from_random_state(&mut self) {
         self.reset();
-        self.agent_pos = random_range(0..5);
+        self.agent_pos = self.rng.random_range(0..5);
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 
     fn state_id(&self) -> usize {
@@ -111,7 +121,10 @@ mod tests {
 
     #[test]
     fn test_line_world_monte_carlo_initial_state() {
-        let mut env = LineWorld { agent_pos: 0 };
+        let mut env = LineWorld {
+            agent_pos: 0,
+            rng: default_rng(ENV_STREAM),
+        };
         env.reset();
         // Position initiale
         assert_eq!(env.agent_pos, 2);
@@ -126,7 +139,10 @@ mod tests {
 
     #[test]
     fn test_line_world_monte_carlo_step_and_terminal() {
-        let mut env = LineWorld { agent_pos: 0 };
+        let mut env = LineWorld {
+            agent_pos: 0,
+            rng: default_rng(ENV_STREAM),
+        };
         env.reset(); // agent_pos = 2
 
         // Un pas vers la gauche
@@ -159,7 +175,10 @@ mod tests {
 
     #[test]
     fn test_display_line_world() {
-        let mut env = LineWorld { agent_pos: 0 };
+        let mut env = LineWorld {
+            agent_pos: 0,
+            rng: default_rng(ENV_STREAM),
+        };
         env.reset();
         env.display();
     }
@@ -5,3 +5,5 @@ pub mod monty_hall_lvl1;
 // pub mod monty_hall_lvl2;
 //
 pub mod secret_envs;
+pub mod plugin_env;
+pub mod ffi;
@@ -1,26 +1,28 @@
 use crate::core::envs::{
     DPEnvironment, DynamicProgramingEnvironment, Environment, MonteCarloEnvironment,
 };
-use rand::random_range;
+use crate::core::rng::{default_rng, ENV_STREAM};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 ///## Actions:
 ///* Porte 1
 ///* Porte 2
 ///* Porte 3
 ///
-/// ## States :
-/// * Etat Initial
-/// * 3 porte ouverte
-/// * 3 protes 1er tour * 2 portes second tour
+/// ## States (partagés par le modèle DP et l'env MC) :
+/// * 0 : état initial
+/// * 1 + c * 3 + d : l'agent a choisi la porte c, l'animateur a supprimé la porte d
+/// * 10 : partie perdue, 11 : partie gagnée
 ///
 /// Au 2ème round, plus que deux actions car plus que 2 portes
 
 pub fn monty_hall_lvl1_dp() -> DPEnvironment {
-    let num_states = 36;
+    let num_states = 12;
     let num_actions = 3;
     let num_rewards = 2;
     let rewards = vec![0.0, 1.0];
-    let terminal_states = vec![4];
+    let terminal_states = vec![10, 11];
 
     let mut env = DPEnvironment::new(
         num_states,
@@ -30,18 +32,23 @@ pub fn monty_hall_lvl1_dp() -> DPEnvironment {
         terminal_states,
     );
 
-    // 1er Round, ouverture d'une porte :
-    for a in 0..num_actions {
-        env.set_transition_prob(0, a, 1 + a, 0, 1.0);
+    // 1er Round, l'agent choisit c, puis une des deux autres portes est supprimée.
+    // Chacune l'est avec probabilité 1/2 (1/3 * 1/2 si c gagne, 1/3 si l'autre porte gagne)
+    for c in 0..num_actions {
+        for d in (0..num_actions).filter(|&d| d != c) {
+            env.set_transition_prob(0, c, 1 + c * 3 + d, 0, 0.5);
+        }
     }
 
-    // 2ᵉ round, une des deux portes non choisies est supprimée, puis l'agent choisit une des autres portes.
+    // 2ᵉ round, l'agent garde sa porte (gagne avec proba 1/3) ou change (2/3).
     // Seulement 2 actions à cause de suppression porte
-    for a in 0..(num_actions - 1) {
-        for s in 1..4 {
-            let s_prime = 4 + (s * num_actions) + a;
-            for r in 0..num_rewards {
-                env.set_transition_prob(s, a, s_prime, r, 0.5);
+    for c in 0..num_actions {
+        for d in (0..num_actions).filter(|&d| d != c) {
+            let s = 1 + c * 3 + d;
+            let other = 3 - c - d;
+            for (a, p_win) in [(c, 1.0 / 3.0), (other, 2.0 / 3.0)] {
+                env.set_transition_prob(s, a, 11, 1, p_win);
+                env.set_transition_prob(s, a, 10, 0, 1.0 - p_win);
             }
         }
     }
@@ -67,12 +74,13 @@ pub struct MontyHallEnv {
     lvl: usize,
     state_id: usize,
     nb_porte_initial: usize,
+    rng: StdRng,
 }
 
 impl Environment for MontyHallEnv {
     fn num_states(&self) -> usize {
         if self.lvl == 1 {
-            7
+            12
         } else if self.lvl == 2 {
             71
         } else {
@@ -96,15 +104,15 @@ impl MonteCarloEnvironment for MontyHallEnv {
                 self.round = 0;
                 self.portes_disponibles = vec![0, 1, 2];
                 self.nb_porte = 3;
-                self.porte_gagnante = random_range(0..3);
+                self.porte_gagnante = self.rng.random_range(0..3);
                 self.porte_choisie = None;
-                self.state_id = self.porte_gagnante;
+                self.state_id = 0;
                 self.nb_porte_initial = 3;
             }
             2 => {
                 self.round = 0;
                 self.nb_porte = 5;
-                self.porte_gagnante = random_range(0..5);
+                self.porte_gagnante = self.rng.random_range(0..5);
                 self.porte_choisie = None;
                 self.portes_disponibles = vec![0, 1, 2, 3, 4];
                 self.state_id = self.porte_gagnante;
@@ -115,18 +123,26 @@ impl MonteCarloEnvironment for MontyHallEnv {
     }
 
     fn step(&mut self, action: usize) -> (usize, f64) {
-        if self.portes_disponibles.contains(&action) && self.nb_porte > 2 {
-            let old_id = self.state_id;
-            let multiplier = self.nb_porte; // 3 au premier tour
-            let action_index = self
-                .portes_disponibles
-                .iter()
-                .position(|&p| p == action)
-                .unwrap();
-            self.state_id = old_id * multiplier + action_index;
+        if self.portes_disponibles.contains(&action) && !self.is_game_over() {
+            if self.lvl == 2 {
+                let old_id = self.state_id;
+                let multiplier = self.nb_porte; // 5 au premier tour
+                let action_index = self
+                    .portes_disponibles
+                    .iter()
+                    .position(|&p| p == action)
+                    .unwrap();
+                self.state_id = old_id * multiplier + action_index;
+            }
 
             self.porte_choisie = Some(action);
             self.round += 1;
+
+            // Au dernier round du lvl 1, l'agent choisit entre les deux portes restantes
+            if self.nb_porte == 2 {
+                self.state_id = 10 + usize::from(action == self.porte_gagnante);
+                return (self.state_id, self.score());
+            }
             self.nb_porte -= 1;
 
             let portes_supprimable: Vec<&usize> = self
@@ -134,14 +150,17 @@ impl MonteCarloEnvironment for MontyHallEnv {
                 .iter()
                 .filter(|&&p| p != self.porte_choisie.unwrap() && p != self.porte_gagnante)
                 .collect();
-            let porte_suprimee = portes_supprimable[random_range(0..portes_supprimable.len())];
+            let porte_suprimee = *portes_supprimable[self.rng.random_range(0..portes_supprimable.len())];
 
             let idx_to_remove = self
                 .portes_disponibles
                 .iter()
-                .position(|&p| p == *porte_suprimee)
+                .position(|&p| p == porte_suprimee)
                 .expect("Porte à supprimer non trouvée");
             self.portes_disponibles.remove(idx_to_remove);
+            if self.lvl == 1 {
+                self.state_id = 1 + action * 3 + porte_suprimee;
+            }
         } else {
             panic!("Action not allowed");
         }
@@ -150,7 +169,7 @@ impl MonteCarloEnvironment for MontyHallEnv {
     }
 
     fn score(&self) -> f64 {
-        if self.nb_porte > 2 {
+        if !self.is_game_over() {
             0.0
         } else {
             if self.porte_choisie.unwrap() == self.porte_gagnante {
@@ -162,7 +181,7 @@ impl MonteCarloEnvironment for MontyHallEnv {
     }
 
     fn is_game_over(&self) -> bool {
-        self.nb_porte == 2
+        self.round == self.nb_rounds()
     }
 
     fn available_actions(&self) -> Vec<usize> {
@@ -170,21 +189,38 @@ impl MonteCarloEnvironment for MontyHallEnv {
     }
 
     fn display(&self) {
-        todo!()
+        println!("=== Monty Hall (round {}) ===", self.round);
+        let portes: String = (0..self.nb_porte_initial)
+            .map(|p| {
+                if self.porte_choisie == Some(p) {
+                    format!("[{}]", p + 1)
+                } else if self.portes_disponibles.contains(&p) {
+                    format!(" {} ", p + 1)
+                } else {
+                    " X ".to_string()
+                }
+            })
+            .collect();
+        println!("{}", portes);
+        if self.is_game_over() {
+            println!("Porte gagnante : {}", self.porte_gagnante + 1);
+        }
     }
 
     fn start_from_random_state(&mut self) {
         self.reset();
 
-        let max_iter = if self.lvl == 1 { 1 } else { 3 };
-
-        for _ in 0..random_range(0..max_iter) {
+        for _ in 0..self.rng.random_range(0..self.nb_rounds()) {
             let available_actions = self.available_actions();
-            let action = available_actions[random_range(0..available_actions.len())];
+            let action = available_actions[self.rng.random_range(0..available_actions.len())];
             self.step(action);
         }
     }
 
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     fn state_id(&self) -> usize {
         self.state_id
     }
@@ -198,6 +234,17 @@ impl MonteCarloEnvironment for MontyHallEnv {
     }
 }
 
+impl MontyHallEnv {
+    /// Le lvl 1 se joue en 2 rounds (choix, puis garder ou changer), le lvl 2 en 3
+    fn nb_rounds(&self) -> usize {
+        if self.lvl == 1 {
+            2
+        } else {
+            3
+        }
+    }
+}
+
 pub fn new_monty_hall(lvl: usize) -> MontyHallEnv {
     let mut env = MontyHallEnv {
         nb_porte: 0,
@@ -208,6 +255,7 @@ pub fn new_monty_hall(lvl: usize) -> MontyHallEnv {
         lvl,
         state_id: 0,
         nb_porte_initial: 0,
+        rng: default_rng(ENV_STREAM),
     };
 
     env.reset();
@@ -223,10 +271,9 @@ mod tests {
     fn test_reset_initializes_state_id_and_ports() {
         let mut env = new_monty_hall(1);
         env.reset();
-        // state_id doit valoir porte_gagnante et dans [0,2]
-        let gid = env.porte_gagnante;
-        assert!(gid < 3);
-        assert_eq!(env.state_id(), gid);
+        // La porte gagnante est tirée dans [0,2] mais ne transparaît pas dans l'état initial
+        assert!(env.porte_gagnante < 3);
+        assert_eq!(env.state_id(), 0);
 
         // portes_disponibles initiales
         assert_eq!(env.available_actions(), vec![0, 1, 2]);
@@ -244,24 +291,37 @@ mod tests {
             porte_choisie: None,
             round: 0,
             portes_disponibles: vec![0, 1, 2],
-            state_id: 2, // on fixe l'état initial = porte gagnante
+            state_id: 0,
             nb_porte_initial: 3,
+            rng: default_rng(ENV_STREAM),
         };
 
-        // On applique l'action 1 (idx = 1 dans [0,1,2])
+        // On choisit la porte 1 : l'animateur ne peut supprimer que la porte 0
         env.step(1);
-        // new_id = old_id * 3 + idx = 2*3 + 1 = 7
-        assert_eq!(env.state_id(), 7);
+        // new_id = 1 + c*3 + d = 1 + 1*3 + 0 = 4
+        assert_eq!(env.state_id(), 4);
 
         // Après un step, nb_porte doit être décrémenté
         assert_eq!(env.nb_porte, 2);
         // round doit avoir été incrémenté
         assert_eq!(env.round, 1);
+        assert!(!env.is_game_over());
 
-        // Et is_game_over devient true
+        // On change pour la porte 2, qui gagne
+        assert_eq!(env.step(2), (11, 1.0));
         assert!(env.is_game_over());
     }
 
+    #[test]
+    fn test_dp_model_matches_switching_odds() {
+        let env = monty_hall_lvl1_dp();
+        // Porte 0 choisie, porte 1 supprimée : garder gagne 1 fois sur 3, changer 2 fois sur 3
+        assert_eq!(env.get_transition_prob(0, 0, 2, 0), 0.5);
+        assert!((env.get_transition_prob(2, 0, 11, 1) - 1.0 / 3.0).abs() < 1e-12);
+        assert!((env.get_transition_prob(2, 2, 11, 1) - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(env.get_transition_prob(2, 1, 11, 1), 0.0);
+    }
+
     #[test]
     #[should_panic(expected = "Action not allowed")]
     fn test_step_invalid_action_panics() {
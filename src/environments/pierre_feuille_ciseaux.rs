@@ -1,12 +1,19 @@
 use crate::core::envs::{
     DPEnvironment, DynamicProgramingEnvironment, Environment, MonteCarloEnvironment,
 };
-use rand::random_range;
+use crate::core::rng::{default_rng, ENV_STREAM};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Actions :
 ///     Pierre = 0
 ///     Feuille = 1
 ///     Ciseau = 2
+///
+/// États (partagés par le modèle DP et l'env MC) :
+///     0 : état initial
+///     1 + a : après le round 1, où l'agent a joué a (l'adversaire le rejouera au round 2)
+///     4 + a * 3 + a_adv : fin de partie, l'agent a joué a et l'adversaire a_adv au round 2
 
 pub fn pierre_feuille_ciseaux_dp() -> DPEnvironment {
     let num_states = 13; // état initial + 3 états possibles + 3*3 états finaux
@@ -34,7 +41,7 @@ pub fn pierre_feuille_ciseaux_dp() -> DPEnvironment {
     for s in 1..4 {
         let a_adv = s - 1;
         for a in 0..num_actions {
-            let s_prime = 4;
+            let s_prime = 4 + a * 3 + a_adv;
             let reward_ind = match (a, a_adv) {
                 // égalité
                 (0, 0) => 1,
@@ -65,6 +72,7 @@ pub struct PierreFeuilleCiseaux {
     last_action: usize,
     adv_action: usize,
     score: f64,
+    rng: StdRng,
 }
 
 impl Environment for PierreFeuilleCiseaux {
@@ -87,6 +95,7 @@ impl PierreFeuilleCiseaux {
             last_action: 0,
             adv_action: 0,
             score: 0.0,
+            rng: default_rng(ENV_STREAM),
         };
         
         env.reset();
@@ -105,7 +114,7 @@ impl MonteCarloEnvironment for PierreFeuilleCiseaux {
     fn step(&mut self, action: usize) -> (usize, f64) {
         if !self.is_game_over() {
             match self.round_number {
-                0 => self.adv_action = random_range(0..3),
+                0 => self.adv_action = self.rng.random_range(0..3),
                 1 => self.adv_action = self.last_action,
                 _ => unreachable!(),
             }
@@ -195,16 +204,22 @@ impl MonteCarloEnvironment for PierreFeuilleCiseaux {
     fn start_from_random_state(&mut self) {
         self.reset();
 
-        let nb_round: usize = random_range(0..3);
+        let nb_round: usize = self.rng.random_range(0..3);
         for _ in 0..nb_round {
-            self.step(random_range(0..3));
+            let action = self.rng.random_range(0..3);
+            self.step(action);
         }
     }
 
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     fn state_id(&self) -> usize {
         match self.round_number {
             0 => 0,
-            1 => 1 + self.adv_action,
+            // Le coup de l'agent détermine le round 2 ; celui de l'adversaire, tiré au hasard, non
+            1 => 1 + self.last_action,
             2 => 4 + self.last_action * 3 + self.adv_action,
             _ => unreachable!(),
         }
@@ -0,0 +1,418 @@
+use crate::core::envs::{DynamicProgramingEnvironment, Environment, MonteCarloEnvironment};
+use crate::environments::secret_envs::{SecretEnv, SecretEnvError, SecretEnvLibrary};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Version de l'ABI attendue dans le manifest des plugins
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Capacités qu'un plugin peut déclarer dans son manifest (bitflags)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginCapabilities(pub u32);
+
+impl PluginCapabilities {
+    /// `new`, `reset`, `step`, `score`, `is_game_over`, `state_id`, `available_actions*`, `is_forbidden`, `from_random_state`, `delete`
+    pub const MC: Self = Self(1);
+    /// `transition_probability`, `reward`
+    pub const DP: Self = Self(1 << 1);
+    /// `display`
+    pub const RENDER: Self = Self(1 << 2);
+    /// `seed`
+    pub const SEED: Self = Self(1 << 3);
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for PluginCapabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Manifest exporté par le plugin via le symbole `<prefix>_manifest`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginManifest {
+    pub abi_version: u32,
+    pub capabilities: u32,
+}
+
+impl PluginManifest {
+    pub fn new(capabilities: PluginCapabilities) -> Self {
+        Self {
+            abi_version: PLUGIN_ABI_VERSION,
+            capabilities: capabilities.0,
+        }
+    }
+
+    pub fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities(self.capabilities)
+    }
+}
+
+/// Environnement chargé depuis une lib partagée exposant l'interface C :
+/// `<prefix>_manifest`, `<prefix>_num_states`, `<prefix>_num_actions`, `<prefix>_num_rewards`
+/// plus les symboles correspondant aux capacités déclarées.
+///
+/// La convention de symboles est celle de `SecretEnv`, sur lequel `PluginEnv` s'appuie :
+/// `env()` / `env_mut()` donnent accès aux opérations faillibles `try_*`.
+pub struct PluginEnv {
+    env: SecretEnv,
+    manifest: PluginManifest,
+}
+
+/// Description d'un plugin : chemin de la lib, préfixe des symboles et
+/// éventuellement un manifest à utiliser si la lib n'en exporte pas
+#[derive(Debug, Clone)]
+pub struct PluginSpec {
+    pub path: PathBuf,
+    pub prefix: String,
+    pub manifest: Option<PluginManifest>,
+}
+
+impl PluginSpec {
+    /// Lit une spec `<chemin de la lib>:<prefix>` (ex. `libs/libmes_envs.so:maze`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec.rsplit_once(':') {
+            Some((path, prefix)) if !path.is_empty() && !prefix.is_empty() => {
+                Ok(PluginEnvBuilder::new(path).prefix(prefix).spec())
+            }
+            _ => Err(format!("Plugin invalide « {} » : attendu <lib>:<prefix>", spec)),
+        }
+    }
+}
+
+pub struct PluginEnvBuilder {
+    spec: PluginSpec,
+}
+
+impl PluginEnvBuilder {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            spec: PluginSpec {
+                path: path.into(),
+                prefix: String::from("env"),
+                manifest: None,
+            },
+        }
+    }
+
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.spec.prefix = prefix.to_string();
+        self
+    }
+
+    /// Manifest utilisé à la place de `<prefix>_manifest`, pour les libs
+    /// qui n'en exportent pas (ex. `libsecret_envs`)
+    pub fn manifest(mut self, manifest: PluginManifest) -> Self {
+        self.spec.manifest = Some(manifest);
+        self
+    }
+
+    pub fn spec(self) -> PluginSpec {
+        self.spec
+    }
+
+    pub fn build(self) -> Result<PluginEnv, Box<dyn std::error::Error>> {
+        PluginEnv::load(&self.spec)
+    }
+}
+
+impl PluginEnv {
+    pub fn builder<P: Into<PathBuf>>(path: P) -> PluginEnvBuilder {
+        PluginEnvBuilder::new(path)
+    }
+
+    pub fn load(spec: &PluginSpec) -> Result<Self, Box<dyn std::error::Error>> {
+        let library = SecretEnvLibrary::open(&spec.path)?;
+
+        let manifest = match spec.manifest {
+            Some(manifest) => manifest,
+            None => unsafe {
+                library.symbol::<unsafe extern "C" fn() -> PluginManifest>(&spec.prefix, "manifest")?()
+            },
+        };
+
+        if manifest.abi_version != PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "Plugin {}: version d'ABI {} non supportée (attendue {})",
+                spec.prefix, manifest.abi_version, PLUGIN_ABI_VERSION
+            )
+            .into());
+        }
+
+        // Vérifie que tous les symboles des capacités déclarées sont présents
+        for name in required_symbols(manifest.capabilities()) {
+            unsafe { library.symbol::<*const c_void>(&spec.prefix, name)? };
+        }
+
+        let env = if manifest.capabilities().contains(PluginCapabilities::MC) {
+            library.instance_with_prefix(0, &spec.prefix)?
+        } else {
+            library.model_with_prefix(0, &spec.prefix)
+        };
+
+        Ok(PluginEnv { env, manifest })
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.env.prefix()
+    }
+
+    pub fn manifest(&self) -> PluginManifest {
+        self.manifest
+    }
+
+    pub fn has(&self, capability: PluginCapabilities) -> bool {
+        self.manifest.capabilities().contains(capability)
+    }
+
+    pub fn env(&self) -> &SecretEnv {
+        &self.env
+    }
+
+    pub fn env_mut(&mut self) -> &mut SecretEnv {
+        &mut self.env
+    }
+
+    /// Réinitialise le générateur aléatoire du plugin. Retourne `Ok(false)` si le plugin
+    /// ne déclare pas la capacité SEED.
+    pub fn try_seed(&mut self, seed: u64) -> Result<bool, SecretEnvError> {
+        if !self.has(PluginCapabilities::SEED) {
+            return Ok(false);
+        }
+        self.env.try_seed(seed)?;
+        Ok(true)
+    }
+
+    fn require(&self, capability: PluginCapabilities, what: &str) {
+        assert!(
+            self.has(capability),
+            "Plugin {} : {} non supporté (capacités {:#b})",
+            self.prefix(),
+            what,
+            self.manifest.capabilities
+        );
+    }
+}
+
+fn required_symbols(capabilities: PluginCapabilities) -> Vec<&'static str> {
+    let mut names = vec!["num_states", "num_actions", "num_rewards"];
+    if capabilities.contains(PluginCapabilities::MC) {
+        names.extend([
+            "new",
+            "delete",
+            "reset",
+            "step",
+            "score",
+            "is_game_over",
+            "state_id",
+            "is_forbidden",
+            "available_actions",
+            "available_actions_len",
+            "available_actions_delete",
+            "from_random_state",
+        ]);
+    }
+    if capabilities.contains(PluginCapabilities::DP) {
+        names.extend(["transition_probability", "reward"]);
+    }
+    if capabilities.contains(PluginCapabilities::RENDER) {
+        names.push("display");
+    }
+    if capabilities.contains(PluginCapabilities::SEED) {
+        names.push("seed");
+    }
+    names
+}
+
+impl Environment for PluginEnv {
+    fn num_states(&self) -> usize {
+        self.env.num_states()
+    }
+
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn num_rewards(&self) -> usize {
+        self.env.num_rewards()
+    }
+}
+
+impl DynamicProgramingEnvironment for PluginEnv {
+    fn get_transition_prob(
+        &self,
+        state: usize,
+        action: usize,
+        state_prime: usize,
+        reward_index: usize,
+    ) -> f64 {
+        self.require(PluginCapabilities::DP, "transition_probability");
+        self.env.get_transition_prob(state, action, state_prime, reward_index)
+    }
+
+    fn set_transition_prob(
+        &mut self,
+        _state: usize,
+        _action: usize,
+        _state_prime: usize,
+        _reward_index: usize,
+        _value: f64,
+    ) {
+    }
+
+    fn get_reward(&self, i: usize) -> f64 {
+        self.require(PluginCapabilities::DP, "reward");
+        self.env.get_reward(i)
+    }
+
+    fn get_terminal_states(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn transitions_from(&self, state: usize, action: usize) -> Vec<(usize, usize, f64)> {
+        self.require(PluginCapabilities::DP, "transition_probability");
+        self.env.transitions_from(state, action)
+    }
+}
+
+impl MonteCarloEnvironment for PluginEnv {
+    fn reset(&mut self) {
+        self.require(PluginCapabilities::MC, "reset");
+        self.env.reset()
+    }
+
+    fn step(&mut self, action: usize) -> (usize, f64) {
+        self.require(PluginCapabilities::MC, "step");
+        self.env.step(action)
+    }
+
+    fn score(&self) -> f64 {
+        self.require(PluginCapabilities::MC, "score");
+        self.env.score()
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.require(PluginCapabilities::MC, "is_game_over");
+        self.env.is_game_over()
+    }
+
+    fn available_actions(&self) -> Vec<usize> {
+        self.require(PluginCapabilities::MC, "available_actions");
+        self.env.available_actions()
+    }
+
+    fn display(&self) {
+        if !self.has(PluginCapabilities::RENDER) {
+            println!("[{}] état {}", self.prefix(), self.state_id());
+            return;
+        }
+        self.env.display()
+    }
+
+    /// Transmis au plugin via `<prefix>_seed` s'il déclare SEED, sans effet sinon
+    fn seed(&mut self, seed: u64) {
+        if let Err(e) = self.try_seed(seed) {
+            panic!("Plugin {} : seed a échoué : {}", self.prefix(), e);
+        }
+    }
+
+    fn start_from_random_state(&mut self) {
+        self.require(PluginCapabilities::MC, "from_random_state");
+        self.env.start_from_random_state()
+    }
+
+    fn state_id(&self) -> usize {
+        self.require(PluginCapabilities::MC, "state_id");
+        self.env.state_id()
+    }
+
+    fn is_forbidden(&self, action: usize) -> bool {
+        self.require(PluginCapabilities::MC, "is_forbidden");
+        self.env.is_forbidden(action)
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, PluginSpec>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, PluginSpec>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Enregistre un plugin sous `name` pour qu'il soit utilisable comme `env_name`
+/// dans `run_experiment`. Le plugin est chargé une première fois pour valider le manifest.
+pub fn register_plugin(name: &str, spec: PluginSpec) -> Result<(), Box<dyn std::error::Error>> {
+    PluginEnv::load(&spec)?;
+    registry().lock().unwrap().insert(name.to_string(), spec);
+    Ok(())
+}
+
+/// Charge une nouvelle instance du plugin enregistré sous `name`
+pub fn load_registered_plugin(name: &str) -> Option<Result<PluginEnv, Box<dyn std::error::Error>>> {
+    let spec = registry().lock().unwrap().get(name).cloned()?;
+    Some(PluginEnv::load(&spec))
+}
+
+pub fn registered_plugins() -> Vec<String> {
+    let mut names: Vec<String> = registry().lock().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Enregistre les plugins passés en ligne de commande (`--plugin <nom>=<lib>:<prefix>`,
+/// répétable) et renvoie les arguments restants
+pub fn register_plugin_args(args: Vec<String>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut rest = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg != "--plugin" {
+            rest.push(arg);
+            continue;
+        }
+        let value = args.next().ok_or("--plugin attend <nom>=<lib>:<prefix>")?;
+        let (name, spec) = value
+            .split_once('=')
+            .ok_or_else(|| format!("Plugin invalide « {} » : attendu <nom>=<lib>:<prefix>", value))?;
+        register_plugin(name, PluginSpec::parse(spec)?)?;
+    }
+    Ok(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plugin_spec() {
+        let spec = PluginSpec::parse("libs/libmes_envs.so:maze").unwrap();
+        assert_eq!(spec.path, PathBuf::from("libs/libmes_envs.so"));
+        assert_eq!(spec.prefix, "maze");
+        assert!(spec.manifest.is_none());
+        // Seul le dernier `:` sépare le préfixe (chemins Windows)
+        assert_eq!(PluginSpec::parse(r"C:\envs\maze.dll:maze").unwrap().prefix, "maze");
+        assert!(PluginSpec::parse("libs/libmes_envs.so").is_err());
+        assert!(PluginSpec::parse("libs/libmes_envs.so:").is_err());
+    }
+
+    #[test]
+    fn test_plugin_args_need_a_value() {
+        let args = vec!["bin".to_string(), "--plugin".to_string()];
+        assert!(register_plugin_args(args).is_err());
+        let args = vec!["bin".to_string(), "line_world".to_string()];
+        assert_eq!(register_plugin_args(args.clone()).unwrap(), args);
+    }
+
+    #[test]
+    fn test_capabilities_contains() {
+        let caps = PluginCapabilities::MC | PluginCapabilities::DP;
+        assert!(caps.contains(PluginCapabilities::MC));
+        assert!(caps.contains(PluginCapabilities::DP));
+        assert!(!caps.contains(PluginCapabilities::SEED));
+    }
+}
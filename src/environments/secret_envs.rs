@@ -1,72 +1,545 @@
-use crate::core::envs::{DynamicProgramingEnvironment, Environment, MonteCarloEnvironment};
+//! Environnements secrets chargés depuis une lib partagée (`libs/libsecret_envs.*`).
+//!
+//! ## Modèle de concurrence
+//! - La lib est chargée une seule fois par `SecretEnvLibrary` et partagée par `Arc` :
+//!   cloner une `SecretEnvLibrary` ou créer des instances ne la recharge pas.
+//! - Chaque `SecretEnv` possède son propre handle d'env C. Il est `Send` (il peut être
+//!   déplacé sur un autre thread) mais pas `Sync` : un même handle ne doit jamais être
+//!   utilisé par deux threads à la fois.
+//! - `SecretEnvPool` crée une instance par worker et lance chaque worker sur son propre
+//!   thread (`std::thread::scope`) avec un accès exclusif à son instance.
+//!
+//! Cela suppose que la lib ne partage pas d'état mutable entre handles distincts, ce que
+//! vérifie `test_pool_matches_sequential_execution`.
+
+use crate::core::envs::{
+    DynamicProgramingEnvironment, Environment, MonteCarloEnvironment, SparseDPEnvironment,
+};
+use crate::core::policies::{load_from_file, save_to_file};
 use std::ffi::c_void;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, io};
+
+/// Variable d'environnement permettant de surcharger le chemin de la lib des secret envs
+pub const SECRET_ENVS_LIB_VAR: &str = "SECRET_ENVS_LIB";
+
+/// Nom du fichier de la lib selon la plateforme
+#[cfg(target_os = "linux")]
+pub const SECRET_ENVS_LIB_NAME: &str = "libsecret_envs.so";
+#[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+pub const SECRET_ENVS_LIB_NAME: &str = "libsecret_envs_intel_macos.dylib";
+#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+pub const SECRET_ENVS_LIB_NAME: &str = "libsecret_envs.dylib";
+#[cfg(windows)]
+pub const SECRET_ENVS_LIB_NAME: &str = "secret_envs.dll";
+
+/// Erreurs possibles lors du chargement ou de l'utilisation d'un `SecretEnv`
+#[derive(Debug)]
+pub enum SecretEnvError {
+    /// La lib n'a pas été trouvée ou n'a pas pu être chargée
+    LibraryNotFound(String),
+    /// Un symbole attendu n'est pas exporté par la lib
+    SymbolMissing(String),
+    /// La lib a renvoyé un pointeur nul à la création de l'env
+    NullHandle,
+    /// L'action ne fait pas partie des actions disponibles dans l'état courant
+    InvalidAction(usize),
+}
+
+impl Display for SecretEnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretEnvError::LibraryNotFound(msg) => write!(f, "Lib introuvable : {}", msg),
+            SecretEnvError::SymbolMissing(name) => write!(f, "Symbole manquant : {}", name),
+            SecretEnvError::NullHandle => write!(f, "La lib a renvoyé un env nul"),
+            SecretEnvError::InvalidAction(a) => write!(f, "Action non disponible : {}", a),
+        }
+    }
+}
+
+impl std::error::Error for SecretEnvError {}
+
+/// Source des symboles : une vraie lib partagée, ou en test une table de fonctions
+/// Rust `extern "C"` qui imite la lib (voir `tests::fake_lib`)
+enum Symbols {
+    Library(libloading::Library),
+    #[cfg(test)]
+    Fake(std::collections::HashMap<String, usize>),
+}
+
+impl Symbols {
+    /// # Safety
+    /// `T` doit être un pointeur de fonction correspondant à la signature C du symbole.
+    unsafe fn get<T: Copy>(&self, symbol: &str) -> Option<T> {
+        match self {
+            Symbols::Library(lib) => lib.get::<T>(symbol.as_bytes()).ok().map(|s| *s),
+            #[cfg(test)]
+            Symbols::Fake(table) => table
+                .get(symbol)
+                .map(|addr| std::mem::transmute_copy::<usize, T>(addr)),
+        }
+    }
+}
 
 pub struct SecretEnv {
-    lib: libloading::Library,
+    lib: Arc<Symbols>,
     env: *mut c_void,
     env_id: usize,
+    prefix: String,
+    lib_path: PathBuf,
+}
+
+/// Construit un `SecretEnv` en résolvant le chemin de la lib dans l'ordre suivant :
+/// 1. le chemin donné explicitement avec `lib_path`
+/// 2. la variable d'environnement `SECRET_ENVS_LIB`
+/// 3. `libs/<lib>` relatif au répertoire courant, puis à chaque parent de l'exécutable
+///
+/// Les symboles sont cherchés sous le préfixe `secret_env_<id>`, ou sous celui donné
+/// avec `symbol_prefix` pour toute autre lib suivant la même convention.
+pub struct SecretEnvBuilder {
+    env_id: usize,
+    lib_path: Option<PathBuf>,
+    prefix: Option<String>,
+}
+
+impl SecretEnvBuilder {
+    pub fn new(env_id: usize) -> Self {
+        Self {
+            env_id,
+            lib_path: None,
+            prefix: None,
+        }
+    }
+
+    pub fn lib_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.lib_path = Some(path.into());
+        self
+    }
+
+    pub fn symbol_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Retourne le chemin de la lib qui sera chargée, sans la charger
+    pub fn resolve_lib_path(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.lib_path {
+            return Some(path.clone());
+        }
+
+        if let Some(path) = std::env::var_os(SECRET_ENVS_LIB_VAR) {
+            return Some(PathBuf::from(path));
+        }
+
+        search_paths().into_iter().find(|p| p.is_file())
+    }
+
+    pub fn build(self) -> Result<SecretEnv, SecretEnvError> {
+        let env_id = self.env_id;
+        let prefix = self
+            .prefix
+            .clone()
+            .unwrap_or_else(|| format!("secret_env_{}", env_id));
+
+        self.build_library()?.instance_with_prefix(env_id, &prefix)
+    }
+
+    /// Charge uniquement la lib, pour créer ensuite plusieurs instances qui la partagent
+    pub fn build_library(self) -> Result<SecretEnvLibrary, SecretEnvError> {
+        let path = self.resolve_lib_path().ok_or_else(|| {
+            SecretEnvError::LibraryNotFound(format!(
+                "{} (chemin explicite, ${} ou libs/ près de l'exécutable)",
+                SECRET_ENVS_LIB_NAME, SECRET_ENVS_LIB_VAR
+            ))
+        })?;
+        SecretEnvLibrary::open(path)
+    }
+}
+
+/// Lib de secret envs chargée une fois et partagée entre toutes ses instances
+#[derive(Clone)]
+pub struct SecretEnvLibrary {
+    lib: Arc<Symbols>,
+    path: PathBuf,
+}
+
+impl SecretEnvLibrary {
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, SecretEnvError> {
+        let path = path.into();
+        let lib = unsafe { libloading::Library::new(&path) }
+            .map_err(|e| SecretEnvError::LibraryNotFound(format!("{}: {}", path.display(), e)))?;
+
+        Ok(SecretEnvLibrary {
+            lib: Arc::new(Symbols::Library(lib)),
+            path,
+        })
+    }
+
+    /// Lib factice construite à partir d'une table `nom du symbole -> fonction`
+    #[cfg(test)]
+    fn fake(symbols: Vec<(String, usize)>) -> Self {
+        SecretEnvLibrary {
+            lib: Arc::new(Symbols::Fake(symbols.into_iter().collect())),
+            path: PathBuf::from("<fake>"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Nouvelle instance de `secret_env_<env_id>`
+    pub fn instance(&self, env_id: usize) -> Result<SecretEnv, SecretEnvError> {
+        self.instance_with_prefix(env_id, &format!("secret_env_{}", env_id))
+    }
+
+    /// Nouvelle instance utilisant les symboles `<prefix>_*`
+    pub fn instance_with_prefix(
+        &self,
+        env_id: usize,
+        prefix: &str,
+    ) -> Result<SecretEnv, SecretEnvError> {
+        let mut env = self.model_with_prefix(env_id, prefix);
+
+        let new_fn = unsafe { env.get::<unsafe extern "C" fn() -> *mut c_void>("new")? };
+        let handle = unsafe { new_fn() };
+        if handle.is_null() {
+            return Err(SecretEnvError::NullHandle);
+        }
+        env.env = handle;
+
+        Ok(env)
+    }
+
+    /// Accès aux symboles `<prefix>_*` sans créer d'instance (`<prefix>_new` n'est pas
+    /// appelé) : seules les opérations sans état (dimensions, modèle DP) sont utilisables
+    pub(crate) fn model_with_prefix(&self, env_id: usize, prefix: &str) -> SecretEnv {
+        SecretEnv {
+            lib: Arc::clone(&self.lib),
+            env: std::ptr::null_mut(),
+            env_id,
+            prefix: prefix.to_string(),
+            lib_path: self.path.clone(),
+        }
+    }
+
+    /// Résout le symbole `<prefix>_<name>` sans instance.
+    ///
+    /// # Safety
+    /// `T` doit correspondre à la signature C exportée par la lib.
+    pub(crate) unsafe fn symbol<T: Copy>(&self, prefix: &str, name: &str) -> Result<T, SecretEnvError> {
+        let symbol = format!("{}_{}", prefix, name);
+        self.lib
+            .get(&symbol)
+            .ok_or(SecretEnvError::SymbolMissing(symbol))
+    }
+}
+
+/// Chemins candidats pour la lib : `./libs` puis `libs/` dans chaque ancêtre de l'exécutable
+/// (ce qui couvre `target/debug`, `target/debug/deps` et une install à côté du binaire)
+fn search_paths() -> Vec<PathBuf> {
+    let mut paths = vec![Path::new("libs").join(SECRET_ENVS_LIB_NAME)];
+
+    if let Ok(exe) = std::env::current_exe() {
+        for dir in exe.ancestors().skip(1) {
+            paths.push(dir.join("libs").join(SECRET_ENVS_LIB_NAME));
+        }
+    }
+
+    paths
 }
 
 impl SecretEnv {
-    pub fn new(env_id: usize) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(env_id: usize) -> Result<Self, SecretEnvError> {
+        SecretEnvBuilder::new(env_id).build()
+    }
+
+    pub fn builder(env_id: usize) -> SecretEnvBuilder {
+        SecretEnvBuilder::new(env_id)
+    }
+
+    pub fn env_id(&self) -> usize {
+        self.env_id
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Crée une nouvelle instance indépendante du même env, qui partage la lib déjà chargée
+    pub fn new_instance(&self) -> Result<SecretEnv, SecretEnvError> {
+        self.library().instance_with_prefix(self.env_id, &self.prefix)
+    }
+
+    pub fn library(&self) -> SecretEnvLibrary {
+        SecretEnvLibrary {
+            lib: Arc::clone(&self.lib),
+            path: self.lib_path.clone(),
+        }
+    }
+
+    pub fn lib_path(&self) -> &Path {
+        &self.lib_path
+    }
+
+    /// Hash FNV-1a du contenu de la lib, utilisé comme clé du cache de snapshots
+    pub fn library_hash(&self) -> io::Result<u64> {
+        let bytes = fs::read(&self.lib_path)?;
+        Ok(bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        }))
+    }
+
+    /// Extrait tout le modèle de transition de la lib en un seul parcours,
+    /// avec détection des états terminaux (voir `SparseDPEnvironment::from_env`)
+    pub fn dp_snapshot(&self) -> SparseDPEnvironment {
+        SparseDPEnvironment::from_env(self)
+    }
+
+    /// Comme `dp_snapshot`, mais relit `<cache_dir>/<prefix>_<hash de la lib>.json` s'il existe
+    /// et l'écrit sinon. Un changement de la lib invalide donc automatiquement le cache.
+    pub fn dp_snapshot_cached(&self, cache_dir: &Path) -> io::Result<SparseDPEnvironment> {
+        let path = cache_dir.join(format!("{}_{:016x}.json", self.prefix, self.library_hash()?));
+        let path_str = path.to_string_lossy();
+
+        if path.is_file() {
+            return load_from_file(&path_str);
+        }
+
+        let snapshot = self.dp_snapshot();
+        fs::create_dir_all(cache_dir)?;
+        save_to_file(&snapshot, &path_str)?;
+        Ok(snapshot)
+    }
+
+    fn symbol(&self, name: &str) -> String {
+        format!("{}_{}", self.prefix, name)
+    }
+
+    /// Résout le symbole `<prefix>_<name>`.
+    ///
+    /// # Safety
+    /// `T` doit correspondre à la signature C exportée par la lib.
+    unsafe fn get<T: Copy>(&self, name: &str) -> Result<T, SecretEnvError> {
+        let symbol = self.symbol(name);
+        self.lib
+            .get(&symbol)
+            .ok_or(SecretEnvError::SymbolMissing(symbol))
+    }
+
+    fn handle(&self) -> Result<*mut c_void, SecretEnvError> {
+        if self.env.is_null() {
+            Err(SecretEnvError::NullHandle)
+        } else {
+            Ok(self.env)
+        }
+    }
+
+    fn count(&self, name: &str) -> Result<usize, SecretEnvError> {
+        unsafe { Ok(self.get::<unsafe extern "C" fn() -> usize>(name)?()) }
+    }
+
+    pub fn try_num_states(&self) -> Result<usize, SecretEnvError> {
+        self.count("num_states")
+    }
+
+    pub fn try_num_actions(&self) -> Result<usize, SecretEnvError> {
+        self.count("num_actions")
+    }
+
+    pub fn try_num_rewards(&self) -> Result<usize, SecretEnvError> {
+        self.count("num_rewards")
+    }
+
+    pub fn try_transition_prob(
+        &self,
+        state: usize,
+        action: usize,
+        state_prime: usize,
+        reward_index: usize,
+    ) -> Result<f64, SecretEnvError> {
         unsafe {
-            //Selection de la bonne lib selon l'environnement
-            #[cfg(target_os = "linux")]
-            let path = "./libs/libsecret_envs.so";
-            #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-            let path = "./libs/libsecret_envs_intel_macos.dylib";
-            #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-            let path = "./libs/libsecret_envs.dylib";
-            #[cfg(windows)]
-            let path = "./libs/secret_envs.dll";
+            let get_transition_prob_fn = self
+                .get::<unsafe extern "C" fn(usize, usize, usize, usize) -> f32>(
+                    "transition_probability",
+                )?;
+            Ok(get_transition_prob_fn(state, action, state_prime, reward_index) as f64)
+        }
+    }
 
-            // Chargement de la lib
-            let lib = libloading::Library::new(path).expect("Failed to load library");
+    pub fn try_reward(&self, i: usize) -> Result<f64, SecretEnvError> {
+        unsafe {
+            let get_reward_fn = self.get::<unsafe extern "C" fn(usize) -> f32>("reward")?;
+            Ok(get_reward_fn(i) as f64)
+        }
+    }
 
-            let new_fn: libloading::Symbol<unsafe extern "C" fn() -> *mut c_void> =
-                lib.get(format!("secret_env_{}_new", env_id).as_bytes())?;
-            let env = new_fn();
+    /// Transitions non nulles depuis (state, action), avec un seul lookup de symbole
+    pub fn try_transitions_from(
+        &self,
+        state: usize,
+        action: usize,
+    ) -> Result<Vec<(usize, usize, f64)>, SecretEnvError> {
+        let num_states = self.try_num_states()?;
+        let num_rewards = self.try_num_rewards()?;
+        unsafe {
+            let get_transition_prob_fn = self
+                .get::<unsafe extern "C" fn(usize, usize, usize, usize) -> f32>(
+                    "transition_probability",
+                )?;
 
-            Ok(SecretEnv { lib, env, env_id })
+            let mut transitions = Vec::new();
+            for state_prime in 0..num_states {
+                for reward_index in 0..num_rewards {
+                    let p = get_transition_prob_fn(state, action, state_prime, reward_index);
+                    if p != 0.0 {
+                        transitions.push((state_prime, reward_index, p as f64));
+                    }
+                }
+            }
+            Ok(transitions)
         }
     }
-}
 
-impl Environment for SecretEnv {
-    fn num_states(&self) -> usize {
+    pub fn try_reset(&mut self) -> Result<(), SecretEnvError> {
+        let env = self.handle()?;
         unsafe {
-            let num_states_fn: libloading::Symbol<unsafe extern "C" fn() -> usize> = self
-                .lib
-                .get(format!("secret_env_{}_num_states", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction num_states");
+            self.get::<unsafe extern "C" fn(*mut c_void)>("reset")?(env);
+        }
+        Ok(())
+    }
 
-            num_states_fn()
+    /// Joue `action` après avoir vérifié qu'elle est disponible : la lib termine le
+    /// processus sur une action interdite, on renvoie donc `InvalidAction` avant l'appel.
+    /// La vérification n'alloue rien : la lib termine aussi le processus si `is_forbidden`
+    /// reçoit une action hors bornes, et ne l'utilise pas pour signaler la fin de l'épisode.
+    pub fn try_step(&mut self, action: usize) -> Result<(usize, f64), SecretEnvError> {
+        let env = self.handle()?;
+        if action >= self.try_num_actions()?
+            || self.try_is_game_over()?
+            || self.try_is_forbidden(action)?
+        {
+            return Err(SecretEnvError::InvalidAction(action));
+        }
+        unsafe {
+            self.get::<unsafe extern "C" fn(*mut c_void, usize)>("step")?(env, action);
         }
+
+        Ok((self.try_state_id()?, self.try_score()?))
     }
 
-    fn num_actions(&self) -> usize {
+    pub fn try_score(&self) -> Result<f64, SecretEnvError> {
+        let env = self.handle()?;
+        unsafe { Ok(self.get::<unsafe extern "C" fn(*const c_void) -> f32>("score")?(env) as f64) }
+    }
+
+    pub fn try_is_game_over(&self) -> Result<bool, SecretEnvError> {
+        let env = self.handle()?;
+        unsafe { Ok(self.get::<unsafe extern "C" fn(*const c_void) -> bool>("is_game_over")?(env)) }
+    }
+
+    pub fn try_available_actions(&self) -> Result<Vec<usize>, SecretEnvError> {
+        let env = self.handle()?;
         unsafe {
-            let num_actions_fn: libloading::Symbol<unsafe extern "C" fn() -> usize> = self
-                .lib
-                .get(format!("secret_env_{}_num_actions", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction num_actions");
+            let available_actions_fn =
+                self.get::<unsafe extern "C" fn(*const c_void) -> *const usize>("available_actions")?;
+            let available_actions_len_fn =
+                self.get::<unsafe extern "C" fn(*const c_void) -> usize>("available_actions_len")?;
+            let available_actions_destroy_fn =
+                self.get::<unsafe extern "C" fn(*const usize, usize)>("available_actions_delete")?;
+
+            let available_actions = available_actions_fn(env);
+            let available_actions_len = available_actions_len_fn(env);
+            if available_actions.is_null() {
+                return Ok(Vec::new());
+            }
 
-            num_actions_fn()
+            let mut actions = Vec::with_capacity(available_actions_len);
+            for i in 0..available_actions_len {
+                actions.push(*available_actions.add(i));
+            }
+
+            available_actions_destroy_fn(available_actions, available_actions_len);
+
+            Ok(actions)
         }
     }
 
-    fn num_rewards(&self) -> usize {
+    pub fn try_display(&self) -> Result<(), SecretEnvError> {
+        let env = self.handle()?;
+        unsafe {
+            self.get::<unsafe extern "C" fn(*const c_void)>("display")?(env);
+        }
+        Ok(())
+    }
+
+    /// Remplace l'instance courante par une instance partant d'un état aléatoire,
+    /// l'ancienne instance est libérée.
+    pub fn try_start_from_random_state(&mut self) -> Result<(), SecretEnvError> {
+        unsafe {
+            let start_from_random_state_fn =
+                self.get::<unsafe extern "C" fn() -> *mut c_void>("from_random_state")?;
+            let delete_fn = self.get::<unsafe extern "C" fn(*mut c_void)>("delete")?;
+
+            let new_env = start_from_random_state_fn();
+            if new_env.is_null() {
+                return Err(SecretEnvError::NullHandle);
+            }
+            if !self.env.is_null() {
+                delete_fn(self.env);
+            }
+            self.env = new_env;
+        }
+        Ok(())
+    }
+
+    /// Réinitialise le générateur aléatoire de l'instance via `<prefix>_seed`, que
+    /// `libsecret_envs` n'exporte pas (voir `PluginCapabilities::SEED`)
+    pub fn try_seed(&mut self, seed: u64) -> Result<(), SecretEnvError> {
+        let env = self.handle()?;
         unsafe {
-            let num_rewards_fn: libloading::Symbol<unsafe extern "C" fn() -> usize> = self
-                .lib
-                .get(format!("secret_env_{}_num_rewards", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction num_rewards");
+            self.get::<unsafe extern "C" fn(*mut c_void, u64)>("seed")?(env, seed);
+        }
+        Ok(())
+    }
 
-            num_rewards_fn()
+    pub fn try_state_id(&self) -> Result<usize, SecretEnvError> {
+        let env = self.handle()?;
+        unsafe { Ok(self.get::<unsafe extern "C" fn(*const c_void) -> usize>("state_id")?(env)) }
+    }
+
+    pub fn try_is_forbidden(&self, action: usize) -> Result<bool, SecretEnvError> {
+        let env = self.handle()?;
+        unsafe {
+            Ok(self.get::<unsafe extern "C" fn(*const c_void, usize) -> bool>("is_forbidden")?(
+                env, action,
+            ))
         }
     }
 }
 
+/// Les implémentations des traits paniquent avec le message de `SecretEnvError` :
+/// utiliser les variantes `try_` pour gérer les erreurs.
+fn or_panic<T>(result: Result<T, SecretEnvError>) -> T {
+    result.unwrap_or_else(|e| panic!("Erreur SecretEnv : {}", e))
+}
+
+impl Environment for SecretEnv {
+    fn num_states(&self) -> usize {
+        or_panic(self.try_num_states())
+    }
+
+    fn num_actions(&self) -> usize {
+        or_panic(self.try_num_actions())
+    }
+
+    fn num_rewards(&self) -> usize {
+        or_panic(self.try_num_rewards())
+    }
+}
+
 impl DynamicProgramingEnvironment for SecretEnv {
     fn get_transition_prob(
         &self,
@@ -75,16 +548,7 @@ impl DynamicProgramingEnvironment for SecretEnv {
         state_prime: usize,
         reward_index: usize,
     ) -> f64 {
-        unsafe {
-            let get_transition_prob_fn: libloading::Symbol<
-                unsafe extern "C" fn(usize, usize, usize, usize) -> f32,
-            > = self
-                .lib
-                .get(b"secret_env_0_transition_probability")
-                .expect("Failed to load function `secret_env_0_transition_probability`");
-
-            get_transition_prob_fn(state, action, state_prime, reward_index) as f64
-        }
+        or_panic(self.try_transition_prob(state, action, state_prime, reward_index))
     }
 
     fn set_transition_prob(
@@ -95,166 +559,124 @@ impl DynamicProgramingEnvironment for SecretEnv {
         _reward_index: usize,
         _value: f64,
     ) {
-        return;
     }
 
     fn get_reward(&self, i: usize) -> f64 {
-        unsafe {
-            let get_reward_fn: libloading::Symbol<unsafe extern "C" fn(usize) -> f32> = self
-                .lib
-                .get(format!("secret_env_{}_reward", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction reward");
-            get_reward_fn(i) as f64
-        }
+        or_panic(self.try_reward(i))
     }
 
     fn get_terminal_states(&self) -> Vec<usize> {
         Vec::new()
     }
+
+    fn transitions_from(&self, state: usize, action: usize) -> Vec<(usize, usize, f64)> {
+        or_panic(self.try_transitions_from(state, action))
+    }
 }
 
 impl MonteCarloEnvironment for SecretEnv {
     fn reset(&mut self) {
-        unsafe {
-            let reset_fn: libloading::Symbol<unsafe extern "C" fn(*mut c_void)> = self
-                .lib
-                .get(format!("secret_env_{}_reset", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction reset");
-
-            reset_fn(self.env);
-        }
+        or_panic(self.try_reset())
     }
 
     fn step(&mut self, action: usize) -> (usize, f64) {
-        unsafe {
-            let step_fn: libloading::Symbol<unsafe extern "C" fn(*mut c_void, usize)> = self
-                .lib
-                .get(format!("secret_env_{}_step", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction step");
-
-            step_fn(self.env, action);
-        }
-
-        (self.state_id(), self.score())
+        or_panic(self.try_step(action))
     }
 
     fn score(&self) -> f64 {
-        unsafe {
-            let score_fn: libloading::Symbol<unsafe extern "C" fn(*const c_void) -> f32> = self
-                .lib
-                .get(format!("secret_env_{}_score", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction score");
-
-            score_fn(self.env) as f64
-        }
+        or_panic(self.try_score())
     }
 
     fn is_game_over(&self) -> bool {
-        unsafe {
-            let is_game_over_fn: libloading::Symbol<unsafe extern "C" fn(*const c_void) -> bool> =
-                self.lib
-                    .get(format!("secret_env_{}_is_game_over", self.env_id).as_bytes())
-                    .expect("Erreur : impossible de trouver la fonction is_game_over");
-
-            is_game_over_fn(self.env)
-        }
+        or_panic(self.try_is_game_over())
     }
 
     fn available_actions(&self) -> Vec<usize> {
-        unsafe {
-            let available_actions_fn: libloading::Symbol<
-                unsafe extern "C" fn(*const c_void) -> *const usize,
-            > = self
-                .lib
-                .get(format!("secret_env_{}_available_actions", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction available_actions");
-
-            let available_actions_len_fn: libloading::Symbol<
-                unsafe extern "C" fn(*const c_void) -> usize,
-            > = self
-                .lib
-                .get(format!("secret_env_{}_available_actions_len", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction available_actions_len");
-
-            let available_actions_destroy_fn: libloading::Symbol<
-                unsafe extern "C" fn(*const usize, usize),
-            > = self
-                .lib
-                .get(format!("secret_env_{}_available_actions_delete", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction available_actions_delete");
-
-            let available_actions = available_actions_fn(self.env);
-            let available_actions_len = available_actions_len_fn(self.env);
-
-            let mut actions = Vec::with_capacity(available_actions_len);
-            for i in 0..available_actions_len {
-                actions.push(*available_actions.add(i));
-            }
+        or_panic(self.try_available_actions())
+    }
 
-            available_actions_destroy_fn(available_actions, available_actions_len);
+    fn display(&self) {
+        or_panic(self.try_display())
+    }
 
-            actions
-        }
+    fn start_from_random_state(&mut self) {
+        or_panic(self.try_start_from_random_state())
     }
 
-    fn display(&self) {
-        unsafe {
-            let display_fn: libloading::Symbol<unsafe extern "C" fn(*const c_void)> = self
-                .lib
-                .get(format!("secret_env_{}_display", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction display");
+    fn state_id(&self) -> usize {
+        or_panic(self.try_state_id())
+    }
 
-            display_fn(self.env);
-        }
+    fn is_forbidden(&self, action: usize) -> bool {
+        or_panic(self.try_is_forbidden(action))
     }
+}
 
-    fn start_from_random_state(&mut self) {
-        unsafe {
-            let start_from_random_state_fn: libloading::Symbol<
-                unsafe extern "C" fn() -> *mut c_void,
-            > = self
-                .lib
-                .get(format!("secret_env_{}_from_random_state", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction from_random_state");
+// SAFETY: le handle `env` appartient exclusivement à cette instance et n'est jamais partagé ;
+// la lib est partagée via `Arc` et n'est déchargée qu'après la dernière instance.
+// `SecretEnv` n'est volontairement pas `Sync` (voir la doc du module).
+unsafe impl Send for SecretEnv {}
 
-            self.env = start_from_random_state_fn();
-        }
+/// Pool d'instances d'un même secret env, une par worker
+pub struct SecretEnvPool {
+    envs: Vec<SecretEnv>,
+}
+
+impl SecretEnvPool {
+    /// Crée `size` instances partageant la lib de `template`
+    pub fn new(template: &SecretEnv, size: usize) -> Result<Self, SecretEnvError> {
+        let envs = (0..size)
+            .map(|_| template.new_instance())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(SecretEnvPool { envs })
     }
 
-    fn state_id(&self) -> usize {
-        unsafe {
-            let state_id_fn: libloading::Symbol<unsafe extern "C" fn(*const c_void) -> usize> =
-                self.lib
-                    .get(format!("secret_env_{}_state_id", self.env_id).as_bytes())
-                    .expect("Erreur : impossible de trouver la fonction state_id");
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
 
-            state_id_fn(self.env)
-        }
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
     }
 
-    fn is_forbidden(&self, action: usize) -> bool {
-        unsafe {
-            let is_forbidden_fn: libloading::Symbol<
-                unsafe extern "C" fn(*const c_void, usize) -> bool,
-            > = self
-                .lib
-                .get(format!("secret_env_{}_is_forbidden", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction is_forbidden");
+    /// Lance `f(worker_id, env)` sur un thread par instance et renvoie les résultats
+    /// dans l'ordre des workers
+    pub fn run<R, F>(&mut self, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(usize, &mut SecretEnv) -> R + Sync,
+    {
+        let f = &f;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .envs
+                .iter_mut()
+                .enumerate()
+                .map(|(worker_id, env)| scope.spawn(move || f(worker_id, env)))
+                .collect();
 
-            is_forbidden_fn(self.env, action)
-        }
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("Un worker du pool a paniqué"))
+                .collect()
+        })
+    }
+
+    pub fn into_envs(self) -> Vec<SecretEnv> {
+        self.envs
     }
 }
 
 impl Drop for SecretEnv {
     fn drop(&mut self) {
+        if self.env.is_null() {
+            return;
+        }
+        // Pas de panic dans drop : si `delete` est absent, l'instance est simplement perdue
         unsafe {
-            let delete_fn: libloading::Symbol<unsafe extern "C" fn(*mut c_void)> = self
-                .lib
-                .get(format!("secret_env_{}_delete", self.env_id).as_bytes())
-                .expect("Erreur : impossible de trouver la fonction delete");
-
-            delete_fn(self.env);
+            if let Ok(delete_fn) = self.get::<unsafe extern "C" fn(*mut c_void)>("delete") {
+                delete_fn(self.env);
+            }
         }
     }
 }
@@ -262,6 +684,7 @@ impl Drop for SecretEnv {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::Ordering;
 
     /// Vérifie qu’un SecretEnv avec cet `env_id` peut être instancié
     /// et que ses méthodes de base démarrent sans panic.
@@ -345,8 +768,23 @@ mod tests {
         println!("--- basic_checks terminé pour env {} ---", env_id);
     }
 
+    /// Lib précompilée (`libs/`, `$SECRET_ENVS_LIB`), absente d'un checkout propre : les
+    /// tests qui en ont besoin sont sautés sans elle
+    fn prebuilt_library() -> Option<SecretEnvLibrary> {
+        match SecretEnvBuilder::new(0).build_library() {
+            Ok(library) => Some(library),
+            Err(e) => {
+                eprintln!("Test sauté : {}", e);
+                None
+            }
+        }
+    }
+
     #[test]
     fn test_secret_envs_0_to_3() {
+        if prebuilt_library().is_none() {
+            return;
+        }
         println!("=== Début du test des SecretEnvs 0 à 3 ===");
         for env_id in 0..=3 {
             println!("== Démarrage des tests pour env {} =", env_id);
@@ -354,4 +792,551 @@ mod tests {
         }
         println!("=== Fin du test des SecretEnvs 0 à 3 ===");
     }
+
+    #[test]
+    fn test_builder_explicit_path_takes_precedence() {
+        let builder = SecretEnvBuilder::new(0).lib_path("custom/libsecret.so");
+        assert_eq!(
+            builder.resolve_lib_path(),
+            Some(PathBuf::from("custom/libsecret.so"))
+        );
+    }
+
+    #[test]
+    fn test_builder_missing_library_is_an_error() {
+        let result = SecretEnv::builder(0)
+            .lib_path("libs/n_existe_pas.so")
+            .build();
+        assert!(matches!(result, Err(SecretEnvError::LibraryNotFound(_))));
+    }
+
+    #[test]
+    fn test_missing_symbol_is_an_error() {
+        let _guard = fake_lib::guard();
+        match fake_library().instance_with_prefix(0, "n_existe_pas") {
+            Err(SecretEnvError::SymbolMissing(name)) => assert_eq!(name, "n_existe_pas_new"),
+            Err(e) => panic!("erreur inattendue : {}", e),
+            Ok(_) => panic!("le symbole n'existe pas"),
+        }
+    }
+
+    #[test]
+    fn test_unavailable_action_is_rejected_before_ffi_call() {
+        let _guard = fake_lib::guard();
+        let mut env = fake_library().instance(0).unwrap();
+        env.try_reset().unwrap();
+        let state = env.try_state_id().unwrap();
+
+        let action = env.try_num_actions().unwrap();
+        assert!(matches!(
+            env.try_step(action),
+            Err(SecretEnvError::InvalidAction(a)) if a == action
+        ));
+        // L'env n'a pas bougé
+        assert_eq!(env.try_state_id().unwrap(), state);
+
+        let valid = env.try_available_actions().unwrap()[0];
+        // La vérification ne recopie pas la liste des actions disponibles
+        let buffers = fake_lib::ACTION_BUFFERS.load(Ordering::SeqCst);
+        assert!(env.try_step(valid).is_ok());
+        assert_eq!(fake_lib::ACTION_BUFFERS.load(Ordering::SeqCst), buffers);
+
+        // Plus aucune action n'est valide une fois l'épisode terminé
+        env.step(0);
+        assert!(env.is_game_over());
+        assert!(matches!(env.try_step(0), Err(SecretEnvError::InvalidAction(0))));
+        assert_eq!(fake_lib::ABORTS.load(Ordering::SeqCst), 0);
+    }
+
+    /// Joue quelques épisodes : chaque transition observée doit avoir une probabilité non
+    /// nulle dans le modèle DP lu sous le préfixe de l'env
+    fn check_samples_against_model(env: &mut SecretEnv) {
+        let rewards: Vec<f64> = (0..env.num_rewards()).map(|i| env.get_reward(i)).collect();
+
+        for ep in 0..5 {
+            env.reset();
+            let mut t = 0;
+            while !env.is_game_over() && t < 50 {
+                let s = env.state_id();
+                let actions = env.available_actions();
+                let a = actions[(ep + t / 2) % actions.len()];
+                let score_before = env.score();
+                env.step(a);
+                let s_prime = env.state_id();
+                let r = env.score() - score_before;
+
+                let r_index = rewards
+                    .iter()
+                    .position(|&x| (x - r).abs() < 1e-6)
+                    .unwrap_or_else(|| panic!("reward {} absente de {:?}", r, rewards));
+                let p = env.get_transition_prob(s, a, s_prime, r_index);
+                assert!(
+                    p > 0.0,
+                    "P({} | {}, {}) = {} alors que la transition a été observée",
+                    s_prime,
+                    s,
+                    a,
+                    p
+                );
+                t += 1;
+            }
+        }
+    }
+
+    /// Routage des symboles sur la fausse lib : l'env 1 lit son modèle sous `secret_env_1_*`
+    #[test]
+    fn test_secret_env_1_dp_model_matches_sampled_dynamics() {
+        let _guard = fake_lib::guard();
+        let library = SecretEnvLibrary::fake(fake_lib::symbols("secret_env_1", &[]));
+        let mut env = library.instance(1).unwrap();
+        check_samples_against_model(&mut env);
+    }
+
+    /// Sur la vraie lib : le modèle DP de l'env 1 n'est pas celui de l'env 0 et explique
+    /// les transitions jouées dans l'env 1
+    #[test]
+    fn test_prebuilt_secret_env_1_has_its_own_dp_model() {
+        let Some(library) = prebuilt_library() else {
+            return;
+        };
+        let env_0 = library.instance(0).unwrap();
+        let mut env_1 = library.instance(1).unwrap();
+
+        let same_shape = (env_0.num_states(), env_0.num_actions(), env_0.num_rewards())
+            == (env_1.num_states(), env_1.num_actions(), env_1.num_rewards());
+        let differs = !same_shape
+            || (0..env_1.num_rewards()).any(|i| env_0.get_reward(i) != env_1.get_reward(i))
+            || (0..env_1.num_states())
+                .flat_map(|s| (0..env_1.num_actions()).map(move |a| (s, a)))
+                .any(|(s, a)| env_0.transitions_from(s, a) != env_1.transitions_from(s, a));
+        assert!(differs, "l'env 1 expose le modèle DP de l'env 0");
+
+        check_samples_against_model(&mut env_1);
+    }
+
+    #[test]
+    fn test_secret_env_0_snapshot_is_cached_and_detects_terminals() {
+        let _guard = fake_lib::guard();
+        let cache_dir = std::env::temp_dir().join(format!("rl_snapshot_{}", std::process::id()));
+        fs::create_dir_all(&cache_dir).unwrap();
+        // Le cache est indexé par le hash du fichier de la lib
+        let lib_file = cache_dir.join("libfake.so");
+        fs::write(&lib_file, b"v1").unwrap();
+        let library = SecretEnvLibrary { path: lib_file.clone(), ..fake_library() };
+        let env = library.instance(0).unwrap();
+
+        let snapshot = env.dp_snapshot_cached(&cache_dir).unwrap();
+        assert_eq!(snapshot.num_states, env.num_states());
+        assert_eq!(snapshot.terminal_states, vec![0, 4]);
+        assert_eq!(snapshot.num_transitions(), 6);
+
+        // Chaque (s, a) a une distribution de probabilité complète ou aucune transition
+        for row in &snapshot.transitions {
+            let total: f64 = row.iter().map(|&(_, _, p)| p).sum();
+            assert!(row.is_empty() || (total - 1.0).abs() < 1e-4, "somme = {}", total);
+        }
+
+        // Les états terminaux n'apparaissent jamais comme état courant d'un épisode non terminé
+        let mut mc_env = env.new_instance().unwrap();
+        mc_env.reset();
+        while !mc_env.is_game_over() {
+            assert!(!snapshot.terminal_states.contains(&mc_env.state_id()));
+            let a = mc_env.available_actions()[0];
+            mc_env.step(a);
+        }
+        assert!(snapshot.terminal_states.contains(&mc_env.state_id()));
+
+        // Le second appel relit le cache
+        let cached = env.dp_snapshot_cached(&cache_dir).unwrap();
+        assert_eq!(cached.transitions, snapshot.transitions);
+        assert_eq!(cached.terminal_states, snapshot.terminal_states);
+        let cache_files = || fs::read_dir(&cache_dir).unwrap().count();
+        assert_eq!(cache_files(), 2);
+
+        // Une nouvelle version de la lib invalide le cache
+        fs::write(&lib_file, b"v2").unwrap();
+        env.dp_snapshot_cached(&cache_dir).unwrap();
+        assert_eq!(cache_files(), 3);
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    fn assert_send<T: Send>() {}
+
+    /// Joue des épisodes déterministes (50 pas au plus) et renvoie la trajectoire (state_id, score)
+    fn play(env: &mut SecretEnv, seed: usize, episodes: usize) -> Vec<(usize, f64)> {
+        let mut trajectory = Vec::new();
+        for ep in 0..episodes {
+            env.reset();
+            let mut t = 0;
+            while !env.is_game_over() && t < 50 {
+                let actions = env.available_actions();
+                let a = actions[(seed + t / 2 + ep) % actions.len()];
+                trajectory.push(env.step(a));
+                t += 1;
+            }
+        }
+        trajectory
+    }
+
+    #[test]
+    fn test_instances_share_the_library() {
+        assert_send::<SecretEnv>();
+        let _guard = fake_lib::guard();
+
+        let library = fake_library();
+        let a = library.instance(0).unwrap();
+        let b = a.new_instance().unwrap();
+        assert_eq!(Arc::strong_count(&a.lib), 3);
+        drop(library);
+        drop(a);
+        // La lib reste chargée tant qu'une instance existe
+        assert_eq!(b.num_states(), 5);
+        assert_eq!(fake_lib::live_envs(), 1);
+    }
+
+    #[test]
+    fn test_pool_matches_sequential_execution() {
+        let _guard = fake_lib::guard();
+        let mut symbols = fake_lib::symbols("secret_env_0", &[]);
+        symbols.extend(fake_lib::symbols("secret_env_1", &[]));
+        let library = SecretEnvLibrary::fake(symbols);
+
+        for env_id in [0, 1] {
+            let template = library.instance(env_id).unwrap();
+            let workers = 8;
+
+            let sequential: Vec<Vec<(usize, f64)>> = (0..workers)
+                .map(|seed| play(&mut template.new_instance().unwrap(), seed, 5))
+                .collect();
+
+            let mut pool = SecretEnvPool::new(&template, workers).unwrap();
+            assert_eq!(pool.len(), workers);
+            for _ in 0..3 {
+                let concurrent = pool.run(|seed, env| play(env, seed, 5));
+                assert_eq!(concurrent, sequential, "divergence pour l'env {}", env_id);
+            }
+        }
+    }
+
+    /// Implémentation en Rust de l'interface C des secret envs (un line world à 5 états),
+    /// instrumentée pour détecter les fuites et doubles libérations
+    mod fake_lib {
+        use std::collections::{HashMap, HashSet};
+        use std::ffi::c_void;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Mutex, MutexGuard};
+
+        struct FakeEnv {
+            pos: usize,
+            score: f32,
+        }
+
+        static LOCK: Mutex<()> = Mutex::new(());
+        static LIVE_ENVS: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+        static LIVE_BUFFERS: Mutex<Option<HashMap<usize, usize>>> = Mutex::new(None);
+        pub static DOUBLE_FREES: AtomicUsize = AtomicUsize::new(0);
+        /// Appels sur lesquels la vraie lib termine le processus
+        pub static ABORTS: AtomicUsize = AtomicUsize::new(0);
+        pub static ACTION_BUFFERS: AtomicUsize = AtomicUsize::new(0);
+        static RANDOM_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+        /// Sérialise les tests utilisant la fausse lib et remet les compteurs à zéro
+        pub fn guard() -> MutexGuard<'static, ()> {
+            let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            *LIVE_ENVS.lock().unwrap() = Some(HashSet::new());
+            *LIVE_BUFFERS.lock().unwrap() = Some(HashMap::new());
+            DOUBLE_FREES.store(0, Ordering::SeqCst);
+            ABORTS.store(0, Ordering::SeqCst);
+            ACTION_BUFFERS.store(0, Ordering::SeqCst);
+            RANDOM_STARTS.store(0, Ordering::SeqCst);
+            guard
+        }
+
+        pub fn live_envs() -> usize {
+            LIVE_ENVS.lock().unwrap().as_ref().unwrap().len()
+        }
+
+        pub fn live_buffers() -> usize {
+            LIVE_BUFFERS.lock().unwrap().as_ref().unwrap().len()
+        }
+
+        pub fn forget_live_envs() {
+            LIVE_ENVS.lock().unwrap().as_mut().unwrap().clear();
+        }
+
+        fn register(env: FakeEnv) -> *mut c_void {
+            let ptr = Box::into_raw(Box::new(env)) as *mut c_void;
+            LIVE_ENVS
+                .lock()
+                .unwrap()
+                .as_mut()
+                .unwrap()
+                .insert(ptr as usize);
+            ptr
+        }
+
+        unsafe fn env<'a>(ptr: *const c_void) -> &'a mut FakeEnv {
+            assert!(
+                LIVE_ENVS
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .contains(&(ptr as usize)),
+                "utilisation d'un handle libéré"
+            );
+            &mut *(ptr as *mut FakeEnv)
+        }
+
+        extern "C" fn num_states() -> usize {
+            5
+        }
+
+        extern "C" fn num_actions() -> usize {
+            2
+        }
+
+        extern "C" fn num_rewards() -> usize {
+            3
+        }
+
+        extern "C" fn reward(i: usize) -> f32 {
+            [-1.0, 0.0, 1.0][i]
+        }
+
+        extern "C" fn transition_probability(s: usize, a: usize, s_p: usize, r: usize) -> f32 {
+            if s == 0 || s == 4 {
+                return 0.0;
+            }
+            let next = if a == 0 { s - 1 } else { s + 1 };
+            let r_index = match next {
+                0 => 0,
+                4 => 2,
+                _ => 1,
+            };
+            if s_p == next && r == r_index {
+                1.0
+            } else {
+                0.0
+            }
+        }
+
+        extern "C" fn new() -> *mut c_void {
+            register(FakeEnv { pos: 2, score: 0.0 })
+        }
+
+        extern "C" fn from_random_state() -> *mut c_void {
+            let pos = 1 + RANDOM_STARTS.fetch_add(1, Ordering::SeqCst) % 3;
+            register(FakeEnv { pos, score: 0.0 })
+        }
+
+        unsafe extern "C" fn delete(ptr: *mut c_void) {
+            let removed = LIVE_ENVS
+                .lock()
+                .unwrap()
+                .as_mut()
+                .unwrap()
+                .remove(&(ptr as usize));
+            if removed {
+                drop(Box::from_raw(ptr as *mut FakeEnv));
+            } else {
+                DOUBLE_FREES.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        unsafe extern "C" fn reset(ptr: *mut c_void) {
+            let env = env(ptr);
+            env.pos = 2;
+            env.score = 0.0;
+        }
+
+        unsafe extern "C" fn step(ptr: *mut c_void, action: usize) {
+            let env = env(ptr);
+            if action > 1 || env.pos == 0 || env.pos == 4 {
+                // La vraie lib termine le processus ici
+                ABORTS.fetch_add(1, Ordering::SeqCst);
+                return;
+            }
+            env.pos = if action == 0 { env.pos - 1 } else { env.pos + 1 };
+            env.score += match env.pos {
+                0 => -1.0,
+                4 => 1.0,
+                _ => 0.0,
+            };
+        }
+
+        unsafe extern "C" fn score(ptr: *const c_void) -> f32 {
+            env(ptr).score
+        }
+
+        unsafe extern "C" fn is_game_over(ptr: *const c_void) -> bool {
+            let env = env(ptr);
+            env.pos == 0 || env.pos == 4
+        }
+
+        unsafe extern "C" fn state_id(ptr: *const c_void) -> usize {
+            env(ptr).pos
+        }
+
+        /// Comme la vraie lib : ne signale pas la fin de l'épisode, et termine le processus
+        /// sur une action hors bornes
+        unsafe extern "C" fn is_forbidden(ptr: *const c_void, action: usize) -> bool {
+            env(ptr);
+            if action > 1 {
+                ABORTS.fetch_add(1, Ordering::SeqCst);
+                return true;
+            }
+            false
+        }
+
+        unsafe extern "C" fn available_actions_len(ptr: *const c_void) -> usize {
+            if is_game_over(ptr) {
+                0
+            } else {
+                2
+            }
+        }
+
+        unsafe extern "C" fn available_actions(ptr: *const c_void) -> *const usize {
+            ACTION_BUFFERS.fetch_add(1, Ordering::SeqCst);
+            let actions: Vec<usize> = (0..available_actions_len(ptr)).collect();
+            let len = actions.len();
+            let buffer = Box::into_raw(actions.into_boxed_slice()) as *const usize;
+            LIVE_BUFFERS
+                .lock()
+                .unwrap()
+                .as_mut()
+                .unwrap()
+                .insert(buffer as usize, len);
+            buffer
+        }
+
+        unsafe extern "C" fn available_actions_delete(buffer: *const usize, len: usize) {
+            let expected = LIVE_BUFFERS
+                .lock()
+                .unwrap()
+                .as_mut()
+                .unwrap()
+                .remove(&(buffer as usize));
+            match expected {
+                Some(expected) => {
+                    assert_eq!(expected, len, "longueur libérée différente de celle allouée");
+                    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                        buffer as *mut usize,
+                        len,
+                    )));
+                }
+                None => {
+                    DOUBLE_FREES.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        unsafe extern "C" fn display(ptr: *const c_void) {
+            println!("fake env en {}", env(ptr).pos);
+        }
+
+        /// Table des symboles `<prefix>_*`, sans ceux listés dans `omit`
+        pub fn symbols(prefix: &str, omit: &[&str]) -> Vec<(String, usize)> {
+            let table: Vec<(&str, usize)> = vec![
+                ("num_states", num_states as *const () as usize),
+                ("num_actions", num_actions as *const () as usize),
+                ("num_rewards", num_rewards as *const () as usize),
+                ("reward", reward as *const () as usize),
+                ("transition_probability", transition_probability as *const () as usize),
+                ("new", new as *const () as usize),
+                ("from_random_state", from_random_state as *const () as usize),
+                ("delete", delete as *const () as usize),
+                ("reset", reset as *const () as usize),
+                ("step", step as *const () as usize),
+                ("score", score as *const () as usize),
+                ("is_game_over", is_game_over as *const () as usize),
+                ("state_id", state_id as *const () as usize),
+                ("is_forbidden", is_forbidden as *const () as usize),
+                ("available_actions", available_actions as *const () as usize),
+                ("available_actions_len", available_actions_len as *const () as usize),
+                ("available_actions_delete", available_actions_delete as *const () as usize),
+                ("display", display as *const () as usize),
+            ];
+            table
+                .into_iter()
+                .filter(|(name, _)| !omit.contains(name))
+                .map(|(name, f)| (format!("{}_{}", prefix, name), f))
+                .collect()
+        }
+    }
+
+    fn fake_library() -> SecretEnvLibrary {
+        SecretEnvLibrary::fake(fake_lib::symbols("secret_env_0", &[]))
+    }
+
+    #[test]
+    fn test_fake_available_actions_are_copied_and_released() {
+        let _guard = fake_lib::guard();
+        let mut env = fake_library().instance(0).unwrap();
+
+        for _ in 0..100 {
+            assert_eq!(env.available_actions(), vec![0, 1]);
+        }
+        env.step(1);
+        env.step(1);
+        assert!(env.is_game_over());
+        assert!(env.available_actions().is_empty());
+
+        assert_eq!(fake_lib::live_buffers(), 0);
+        assert_eq!(fake_lib::DOUBLE_FREES.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_fake_start_from_random_state_replaces_handle() {
+        let _guard = fake_lib::guard();
+        let mut env = fake_library().instance(0).unwrap();
+
+        for i in 0..10 {
+            env.start_from_random_state();
+            assert_eq!(env.state_id(), 1 + i % 3);
+            // L'ancienne instance est libérée, il n'en reste qu'une
+            assert_eq!(fake_lib::live_envs(), 1);
+        }
+
+        drop(env);
+        assert_eq!(fake_lib::live_envs(), 0);
+        assert_eq!(fake_lib::DOUBLE_FREES.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_fake_drop_releases_every_handle() {
+        let _guard = fake_lib::guard();
+        let template = fake_library().instance(0).unwrap();
+        let other = template.new_instance().unwrap();
+        let mut pool = SecretEnvPool::new(&template, 4).unwrap();
+        assert_eq!(fake_lib::live_envs(), 6);
+
+        let scores = pool.run(|_, env| {
+            env.step(1);
+            env.step(1);
+            env.score()
+        });
+        assert_eq!(scores, vec![1.0; 4]);
+
+        drop(pool);
+        drop(other);
+        drop(template);
+        assert_eq!(fake_lib::live_envs(), 0);
+        assert_eq!(fake_lib::live_buffers(), 0);
+        assert_eq!(fake_lib::DOUBLE_FREES.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_fake_missing_delete_does_not_panic_on_drop() {
+        let _guard = fake_lib::guard();
+        let library = SecretEnvLibrary::fake(fake_lib::symbols("secret_env_0", &["delete"]));
+        let env = library.instance(0).unwrap();
+        drop(env);
+
+        // Sans `delete`, l'instance ne peut pas être libérée
+        assert_eq!(fake_lib::live_envs(), 1);
+        fake_lib::forget_live_envs();
+    }
 }
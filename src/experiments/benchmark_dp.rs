@@ -0,0 +1,95 @@
+use reinforcement_learning_lib::algorithms::dp::parallel::{parallel_value_iteration, resolve_threads};
+use reinforcement_learning_lib::algorithms::dp::prioritized::prioritized_value_iteration;
+use reinforcement_learning_lib::algorithms::dp::sweeps::{
+    async_value_iteration, modified_policy_iteration, value_iteration_sweeps, StateOrder,
+    SweepMode, SweepStats,
+};
+use reinforcement_learning_lib::core::envs::SparseDPEnvironment;
+use reinforcement_learning_lib::core::rng::{seeded_rng, ALGORITHM_STREAM};
+use reinforcement_learning_lib::core::utils::make_dp_env;
+use reinforcement_learning_lib::environments::plugin_env::register_plugin_args;
+use std::env;
+use std::error::Error;
+use std::process::exit;
+use std::time::Instant;
+
+const MAX_ITER: usize = 100_000;
+
+/// Usage : benchmark_dp <env_name> [gamma] [theta]
+///
+/// `env_name` accepte aussi `grid_world_<lignes>x<colonnes>` pour un grand modèle creux.
+/// `--plugin <nom>=<lib>:<prefix>` (répétable) enregistre un plugin utilisable comme `env_name`.
+///
+/// Compare le nombre de balayages et de backups des variantes DP sur le même modèle.
+fn main() {
+    let args = register_plugin_args(env::args().collect()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(2);
+    });
+    if args.len() < 2 {
+        eprintln!("Usage : {} <env_name> [gamma] [theta]", args[0]);
+        exit(2);
+    }
+    if let Err(e) = run(&args[1], args.get(2), args.get(3)) {
+        eprintln!("Benchmark impossible : {}", e);
+        exit(1);
+    }
+}
+
+fn run(env_name: &str, gamma: Option<&String>, theta: Option<&String>) -> Result<(), Box<dyn Error>> {
+    let gamma: f64 = gamma.map_or(Ok(0.99), |g| g.parse())?;
+    let theta: f64 = theta.map_or(Ok(1e-6), |t| t.parse())?;
+    let env_dp = make_dp_env(env_name)?;
+    let env_dp = &*env_dp;
+    let mut rng = seeded_rng(0, ALGORITHM_STREAM);
+
+    println!(
+        "{} : {} états, {} actions, gamma = {}, theta = {:e}",
+        env_name,
+        env_dp.num_states(),
+        env_dp.num_actions(),
+        gamma,
+        theta
+    );
+    println!("{:<28} {:>8} {:>12} {:>12} {:>10}", "variante", "sweeps", "backups", "résidu", "durée");
+
+    let report = |name: &str, start: Instant, stats: SweepStats| {
+        println!(
+            "{:<28} {:>8} {:>12} {:>12.3e} {:>10.2?}",
+            name,
+            stats.sweeps,
+            stats.backups,
+            stats.residual,
+            start.elapsed()
+        );
+    };
+
+    for (name, mode) in [("Jacobi", SweepMode::Jacobi), ("Gauss-Seidel", SweepMode::GaussSeidel)] {
+        let start = Instant::now();
+        let (_, _, stats) = value_iteration_sweeps(env_dp, theta, gamma, MAX_ITER, mode);
+        report(&format!("VI {}", name), start, stats);
+
+        for k in [1, 5, 20] {
+            let start = Instant::now();
+            let (_, _, stats) = modified_policy_iteration(env_dp, theta, gamma, MAX_ITER, k, mode);
+            report(&format!("PI modifiée k={} {}", k, name), start, stats);
+        }
+    }
+
+    for (name, order) in [("cyclique", StateOrder::Cyclic), ("aléatoire", StateOrder::Random)] {
+        let start = Instant::now();
+        let (_, _, stats) = async_value_iteration(env_dp, theta, gamma, MAX_ITER, order, &mut rng);
+        report(&format!("VI asynchrone {}", name), start, stats);
+    }
+
+    let threads = resolve_threads(0);
+    let sparse = SparseDPEnvironment::from_env(env_dp);
+    let start = Instant::now();
+    let (_, _, stats) = parallel_value_iteration(&sparse, theta, gamma, MAX_ITER, threads);
+    report(&format!("VI Jacobi {} threads", threads), start, stats);
+
+    let start = Instant::now();
+    let (_, _, stats) = prioritized_value_iteration(env_dp, theta, gamma, usize::MAX);
+    report("VI priorisée", start, stats);
+    Ok(())
+}
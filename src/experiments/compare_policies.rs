@@ -0,0 +1,84 @@
+use reinforcement_learning_lib::algorithms::dp::compare::compare_policies;
+use reinforcement_learning_lib::core::artifact::Artifact;
+use reinforcement_learning_lib::core::utils::{make_dp_env, make_mc_env};
+use reinforcement_learning_lib::environments::plugin_env::register_plugin_args;
+use std::env;
+use std::error::Error;
+use std::process::exit;
+
+/// Usage : compare_policies <env_name> <candidate.json> <reference.json> [gamma]
+///
+/// Sans gamma explicite, celui enregistré dans l'artefact de référence est utilisé.
+/// Les états atteignables sont ceux de l'état initial de l'env MC, ou de tous les états non
+/// terminaux si l'env n'a qu'un modèle DP.
+/// `--plugin <nom>=<lib>:<prefix>` (répétable) enregistre un plugin utilisable comme `env_name`.
+fn main() {
+    let args = register_plugin_args(env::args().collect()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(2);
+    });
+    if args.len() < 4 {
+        eprintln!("Usage : {} <env_name> <candidate.json> <reference.json> [gamma]", args[0]);
+        exit(2);
+    }
+    if let Err(e) = run(&args[1], &args[2], &args[3], args.get(4)) {
+        eprintln!("Comparaison impossible : {}", e);
+        exit(1);
+    }
+}
+
+fn run(
+    env_name: &str,
+    candidate_path: &str,
+    reference_path: &str,
+    gamma: Option<&String>,
+) -> Result<(), Box<dyn Error>> {
+    let env_dp = make_dp_env(env_name)?;
+    let candidate = Artifact::load(candidate_path)?;
+    let reference = Artifact::load(reference_path)?;
+    candidate.check_env(&*env_dp, Some(env_name))?;
+    reference.check_env(&*env_dp, Some(env_name))?;
+
+    let gamma = match gamma {
+        Some(g) => g.parse()?,
+        None => *reference
+            .metadata
+            .hyperparameters
+            .get("gamma")
+            .ok_or("gamma absent de l'artefact de référence")?,
+    };
+
+    // L'état de départ est celui de l'env MC après reset ; sans env MC (env DP seul),
+    // tous les états non terminaux servent de départ
+    let start_states = match make_mc_env(env_name) {
+        Ok(mut env_mc) => {
+            env_mc.reset();
+            vec![env_mc.state_id()]
+        }
+        Err(e) => {
+            eprintln!("Pas d'env MC ({}) : départ depuis tous les états non terminaux", e);
+            let terminal = env_dp.get_terminal_states();
+            (0..env_dp.num_states()).filter(|s| !terminal.contains(s)).collect()
+        }
+    };
+
+    let comparison = compare_policies(
+        &*env_dp,
+        candidate.policy()?,
+        reference.policy()?,
+        &start_states,
+        1e-8,
+        gamma,
+    );
+    println!(
+        "{} ({}) vs {} ({}) sur {}, gamma = {}",
+        candidate_path,
+        candidate.metadata.algorithm,
+        reference_path,
+        reference.metadata.algorithm,
+        env_name,
+        gamma
+    );
+    println!("{}", comparison);
+    Ok(())
+}
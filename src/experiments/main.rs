@@ -1,5 +1,8 @@
 use reinforcement_learning_lib::algorithms::dp::policy_iteration::policy_iteration;
+use reinforcement_learning_lib::algorithms::exploration::EpsilonGreedy;
+use reinforcement_learning_lib::algorithms::schedule::{Schedule, Schedules};
 use reinforcement_learning_lib::algorithms::planning::{dyna_q::dyna_q, dyna_q_plus::dyna_q_plus};
+use reinforcement_learning_lib::core::rng::{seeded_rng, ALGORITHM_STREAM};
 use reinforcement_learning_lib::algorithms::td::{
     expected_sarsa::expected_sarsa, q_learning::q_learning, sarsa::sarsa,
 };
@@ -30,36 +33,38 @@ fn main() {
     let n = 5;
     let kappa = 0.001;
     let episodes = 10;
+    let mut schedules = Schedules::constant(alpha);
+    let mut rng = seeded_rng(0, ALGORITHM_STREAM);
 
     match choix {
         1 => {
             println!("=== Dyna-Q ===");
             let mut env = LineWorld::new();
-            let (policy, _) = dyna_q(&mut env, alpha, gamma, epsilon, n, episodes);
+            let (policy, _, _) = dyna_q(&mut env, &mut schedules, gamma, &mut EpsilonGreedy::new(epsilon), n, episodes, &mut rng);
             println!("{}", policy);
         }
         2 => {
             println!("=== Dyna-Q+ ===");
             let mut env = LineWorld::new();
-            let (policy, _) = dyna_q_plus(&mut env, alpha, gamma, epsilon, kappa, n, episodes);
+            let (policy, _, _) = dyna_q_plus(&mut env, &mut schedules.with_kappa(Schedule::Constant(kappa)), gamma, &mut EpsilonGreedy::new(epsilon), n, episodes, &mut rng);
             println!("{}", policy);
         }
         3 => {
             println!("=== SARSA ===");
             let mut env = LineWorld::new();
-            let (policy, _) = sarsa(&mut env, alpha, gamma, epsilon, episodes);
+            let (policy, _, _) = sarsa(&mut env, &mut schedules, gamma, &mut EpsilonGreedy::new(epsilon), episodes, &mut rng);
             println!("{}", policy);
         }
         4 => {
             println!("=== Q-Learning ===");
             let mut env = LineWorld::new();
-            let (policy, _) = q_learning(&mut env, alpha, gamma, epsilon, episodes);
+            let (policy, _, _) = q_learning(&mut env, &mut schedules, gamma, &mut EpsilonGreedy::new(epsilon), episodes, &mut rng);
             println!("{}", policy);
         }
         5 => {
             println!("=== Expected SARSA ===");
             let mut env = LineWorld::new();
-            let (policy, _) = expected_sarsa(&mut env, alpha, gamma, epsilon, episodes);
+            let (policy, _, _) = expected_sarsa(&mut env, &mut schedules, gamma, &mut EpsilonGreedy::new(epsilon), episodes, &mut rng);
             println!("{}", policy);
         }
         6 => {
@@ -76,7 +81,7 @@ fn main() {
 fn test_policy_iteration() {
     let env = line_world_dp();
 
-    let (policy, _) = policy_iteration(&env, 0.0001, 0.99, 1000);
+    let (policy, _, _) = policy_iteration(&env, 0.0001, 0.99, 1000);
     let expected = vec![0, 1, 1, 1, 0];
 
     for s in 0..3 {
@@ -1,11 +1,27 @@
-use reinforcement_learning_lib::core::envs::MonteCarloEnvironment;
-use reinforcement_learning_lib::core::manual_run::run_policy;
-use reinforcement_learning_lib::core::policies::{load_from_file, DeterministicPolicy};
-use reinforcement_learning_lib::environments::line_world::LineWorld;
+use reinforcement_learning_lib::core::manual_run::run_saved_policy;
+use reinforcement_learning_lib::core::utils::make_mc_env;
+use reinforcement_learning_lib::environments::plugin_env::register_plugin_args;
+use std::env;
+use std::process::exit;
 
-fn main(){
-    let env: &mut dyn MonteCarloEnvironment = &mut LineWorld::new();
-    let policy:DeterministicPolicy = load_from_file("output/line_world/policy_iteration/policy.json").unwrap();
+/// Usage : run_policy [env_name] [chemin de l'artefact policy.json]
+///
+/// `--plugin <nom>=<lib>:<prefix>` (répétable) enregistre un plugin utilisable comme `env_name`.
+fn main() {
+    let args = register_plugin_args(env::args().collect()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(2);
+    });
+    let env_name = args.get(1).map_or("line_world", |s| s.as_str());
+    let default_path = format!("output/{}/policy_iteration/policy.json", env_name);
+    let path = args.get(2).unwrap_or(&default_path);
 
-    run_policy(env, &policy);
-}
\ No newline at end of file
+    let mut env = make_mc_env(env_name).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1);
+    });
+    if let Err(e) = run_saved_policy(&mut *env, env_name, path) {
+        eprintln!("Impossible de rejouer {} : {}", path, e);
+        exit(1);
+    }
+}
@@ -11,7 +11,9 @@ fn main() {
         kappa: 0.005,
         max_iter: 100_000,
         planning_steps: 30,
+        seed: 0,
         output_dir: "output/secret_env_0/dyna_q/".into(),
+        ..Default::default()
     };
 
     run_experiment(&config);
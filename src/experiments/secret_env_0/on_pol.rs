@@ -11,7 +11,9 @@ fn main() {
         kappa: 0.001,
         max_iter: 200_000,
         planning_steps: 10,
+        seed: 0,
         output_dir: "output/secret_env_0/on_policy_mc/".into(),
+        ..Default::default()
     };
 
     run_experiment(&config);
@@ -11,7 +11,9 @@ fn main() {
         kappa: 0.001,
         max_iter: 500_000,
         planning_steps: 10,
+        seed: 0,
         output_dir: "output/secret_env_2/value_iteration/".into(),
+        ..Default::default()
     };
 
     run_experiment(&config);
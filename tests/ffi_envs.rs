@@ -0,0 +1,231 @@
+//! Tests d'intégration de `SecretEnv` et `PluginEnv` contre la cdylib construite par cette crate.
+
+use reinforcement_learning_lib::core::envs::{
+    DynamicProgramingEnvironment, Environment, MonteCarloEnvironment,
+};
+use reinforcement_learning_lib::core::utils::{try_run_experiment, Config};
+use reinforcement_learning_lib::environments::grid_world::dynamic_programming::grid_world;
+use reinforcement_learning_lib::environments::line_world::line_world_dp;
+use reinforcement_learning_lib::environments::monty_hall_lvl1::monty_hall_lvl1_dp;
+use reinforcement_learning_lib::environments::pierre_feuille_ciseaux::pierre_feuille_ciseaux_dp;
+use reinforcement_learning_lib::environments::plugin_env::{
+    load_registered_plugin, register_plugin, registered_plugins, PluginCapabilities, PluginEnv,
+    PluginManifest, PluginSpec, PLUGIN_ABI_VERSION,
+};
+use reinforcement_learning_lib::environments::secret_envs::SecretEnv;
+use std::path::PathBuf;
+
+/// La cdylib est produite dans `target/<profile>/`, parent du dossier `deps/` du test
+fn cdylib_path() -> PathBuf {
+    let name = format!(
+        "{}reinforcement_learning_lib{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    let exe = std::env::current_exe().unwrap();
+    exe.ancestors()
+        .skip(1)
+        .map(|dir| dir.join(&name))
+        .find(|p| p.is_file())
+        .unwrap_or_else(|| panic!("{} introuvable à côté de {:?}", name, exe))
+}
+
+fn load(prefix: &str) -> SecretEnv {
+    SecretEnv::builder(0)
+        .lib_path(cdylib_path())
+        .symbol_prefix(prefix)
+        .build()
+        .unwrap()
+}
+
+/// Joue des épisodes et vérifie que chaque transition observée est cohérente avec le modèle DP
+fn check_against_model(env: &mut SecretEnv, model: &dyn DynamicProgramingEnvironment) {
+    assert_eq!(env.num_states(), model.num_states());
+    assert_eq!(env.num_actions(), model.num_actions());
+    assert_eq!(env.num_rewards(), model.num_rewards());
+
+    for ep in 0..20 {
+        env.reset();
+        let mut t = 0;
+        while !env.is_game_over() && t < 100 {
+            let s = env.state_id();
+            let actions = env.available_actions();
+            let a = actions[(ep + t) % actions.len()];
+            let score_before = env.score();
+            env.step(a);
+            let r = env.score() - score_before;
+            let r_index = (0..model.num_rewards())
+                .position(|i| (model.get_reward(i) - r).abs() < 1e-6)
+                .unwrap();
+
+            assert_eq!(env.get_reward(r_index), model.get_reward(r_index));
+            assert!(env.get_transition_prob(s, a, env.state_id(), r_index) > 0.0);
+            t += 1;
+        }
+    }
+}
+
+#[test]
+fn test_secret_env_on_line_world_cdylib() {
+    let mut env = load("line_world");
+    check_against_model(&mut env, &line_world_dp());
+
+    env.start_from_random_state();
+    assert!(env.state_id() < env.num_states());
+    env.display();
+}
+
+#[test]
+fn test_secret_env_on_grid_world_cdylib() {
+    let mut env = load("grid_world");
+    check_against_model(&mut env, &grid_world());
+}
+
+#[test]
+fn test_secret_env_on_pierre_feuille_ciseaux_cdylib() {
+    let mut env = load("pierre_feuille_ciseaux");
+    assert_eq!(env.num_states(), 13);
+    assert_eq!(env.num_actions(), 3);
+    for _ in 0..10 {
+        env.reset();
+        assert_eq!(env.state_id(), 0);
+        env.step(0);
+        assert!((1..4).contains(&env.state_id()));
+        env.step(1);
+        assert!(env.is_game_over());
+        assert!((4..13).contains(&env.state_id()));
+    }
+    check_against_model(&mut env, &pierre_feuille_ciseaux_dp());
+}
+
+#[test]
+fn test_secret_env_on_monty_hall_cdylib() {
+    let mut env = load("monty_hall_lvl1");
+    env.reset();
+    assert_eq!(env.available_actions(), vec![0, 1, 2]);
+    env.step(0);
+    assert!(!env.is_game_over());
+    assert_eq!(env.available_actions().len(), 2);
+    env.step(env.available_actions()[1]);
+    assert!(env.is_game_over());
+    assert!(env.is_forbidden(3));
+    env.display();
+
+    check_against_model(&mut env, &monty_hall_lvl1_dp());
+}
+
+#[test]
+fn test_plugin_env_reads_manifest() {
+    let env = PluginEnv::builder(cdylib_path())
+        .prefix("line_world")
+        .build()
+        .unwrap();
+    assert!(env.has(PluginCapabilities::MC));
+    assert!(env.has(PluginCapabilities::DP));
+    assert!(env.has(PluginCapabilities::SEED));
+
+    for prefix in ["monty_hall_lvl1", "pierre_feuille_ciseaux"] {
+        let env = PluginEnv::builder(cdylib_path()).prefix(prefix).build().unwrap();
+        assert!(env.has(PluginCapabilities::MC));
+        assert!(env.has(PluginCapabilities::DP), "{}", prefix);
+    }
+}
+
+#[test]
+fn test_plugin_seed_reaches_the_plugin() {
+    let play = |seed: u64| {
+        let mut env = PluginEnv::builder(cdylib_path())
+            .prefix("pierre_feuille_ciseaux")
+            .build()
+            .unwrap();
+        env.seed(seed);
+        (0..20)
+            .map(|_| {
+                env.reset();
+                while !env.is_game_over() {
+                    env.step(env.available_actions()[0]);
+                }
+                env.score()
+            })
+            .collect::<Vec<f64>>()
+    };
+    assert_eq!(play(3), play(3));
+    assert_ne!(play(3), play(4));
+}
+
+#[test]
+fn test_missing_manifest_is_an_error() {
+    let result = PluginEnv::builder(cdylib_path()).prefix("n_existe_pas").build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_wrong_abi_version_is_rejected() {
+    let manifest = PluginManifest {
+        abi_version: PLUGIN_ABI_VERSION + 1,
+        capabilities: PluginCapabilities::MC.0,
+    };
+    let result = PluginEnv::builder(cdylib_path())
+        .prefix("line_world")
+        .manifest(manifest)
+        .build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_undeclared_symbol_is_rejected() {
+    // Le manifeste est fourni, mais la cdylib n'exporte aucun symbole sous ce préfixe
+    for capability in [PluginCapabilities::MC, PluginCapabilities::DP] {
+        let manifest = PluginManifest::new(capability);
+        let result = PluginEnv::builder(cdylib_path())
+            .prefix("n_existe_pas")
+            .manifest(manifest)
+            .build();
+        assert!(result.is_err(), "{:?}", capability);
+    }
+}
+
+#[test]
+fn test_plugin_through_registry() {
+    let spec = PluginEnv::builder(cdylib_path()).prefix("line_world").spec();
+    register_plugin("test_line_world", spec).unwrap();
+    assert!(registered_plugins().contains(&"test_line_world".to_string()));
+
+    let mut env = load_registered_plugin("test_line_world").unwrap().unwrap();
+    assert_eq!(env.num_rewards(), 3);
+    assert!(env.try_seed(42).unwrap());
+
+    env.reset();
+    let s = env.state_id();
+    let a = env.available_actions()[0];
+    let score_before = env.score();
+    env.step(a);
+    let r = env.score() - score_before;
+    let r_index = (0..env.num_rewards())
+        .position(|i| (env.get_reward(i) - r).abs() < 1e-6)
+        .unwrap();
+    assert!(env.get_transition_prob(s, a, env.state_id(), r_index) > 0.0);
+
+    env.start_from_random_state();
+    assert!(env.state_id() < env.num_states());
+}
+
+#[test]
+fn test_config_registers_plugins_for_the_runner() {
+    let output_dir = std::env::temp_dir().join(format!("plugin_runner_{}", std::process::id()));
+    let cfg = Config {
+        env_name: "lw_plugin".into(),
+        algorithm: "q_learning".into(),
+        max_iter: 200,
+        output_dir: output_dir.to_string_lossy().into_owned(),
+        plugins: vec![(
+            "lw_plugin".into(),
+            PluginSpec::parse(&format!("{}:line_world", cdylib_path().display())).unwrap(),
+        )],
+        ..Default::default()
+    };
+    try_run_experiment(&cfg).unwrap();
+    assert!(output_dir.join("policy.json").is_file());
+    assert!(registered_plugins().contains(&"lw_plugin".to_string()));
+    std::fs::remove_dir_all(&output_dir).unwrap();
+}